@@ -2,4 +2,72 @@
 
 pub trait IdentityInterface<AccountId> {
 	fn is_identified(who: &AccountId) -> bool;
+
+	/// The voice credits `who` receives for every voting round, zero if `who` is not
+	/// identified.
+	fn voice_credits(who: &AccountId) -> u32;
+
+	/// Make `who` identified without going through the usual registration.
+	/// Only available when benchmarking pallets that depend on an identity provider.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn ensure_identified(who: &AccountId);
+}
+
+/// Tells an identity provider whether an identity still backs votes.
+pub trait IdentityInUse<AccountId> {
+	/// Whether `who` holds votes paid for by its identity in a round that has not ended.
+	fn in_use(who: &AccountId) -> bool;
+}
+
+impl<AccountId> IdentityInUse<AccountId> for () {
+	fn in_use(_who: &AccountId) -> bool {
+		false
+	}
+}
+
+/// Prices the votes an account holds on a single proposal.
+///
+/// Implementations must price no votes at nothing, `cost(0) == Some(0)`, and never make more
+/// votes cheaper than fewer, `cost(n) <= cost(n + 1)` with `None` above every price. Refunds
+/// and budgets are computed from the difference in cost, and the most votes a budget buys
+/// is found by searching over `n`.
+pub trait VoteCostFunction {
+	/// The cost of holding `votes` votes on a single proposal, in units of the vote price.
+	/// `None` on overflow.
+	fn cost(votes: u32) -> Option<u128>;
+}
+
+/// Every vote costs the same, `n` votes cost `n`.
+pub struct Linear;
+
+impl VoteCostFunction for Linear {
+	fn cost(votes: u32) -> Option<u128> {
+		Some(votes.into())
+	}
+}
+
+/// Quadratic voting, `n` votes cost `n * n`.
+pub struct Quadratic;
+
+impl VoteCostFunction for Quadratic {
+	fn cost(votes: u32) -> Option<u128> {
+		let votes: u128 = votes.into();
+		votes.checked_mul(votes)
+	}
+}
+
+/// `n` votes cost `n` to the power of `EXPONENT`, which must be non-zero.
+pub struct Power<const EXPONENT: u32>;
+
+impl<const EXPONENT: u32> Power<EXPONENT> {
+	// `0^0 == 1` would make casting no votes cost something.
+	const NON_ZERO_EXPONENT: () = assert!(EXPONENT > 0, "Power needs a non-zero EXPONENT");
+}
+
+impl<const EXPONENT: u32> VoteCostFunction for Power<EXPONENT> {
+	fn cost(votes: u32) -> Option<u128> {
+		// Fails the build for `Power<0>` once the cost is used.
+		let () = Self::NON_ZERO_EXPONENT;
+		u128::from(votes).checked_pow(EXPONENT)
+	}
 }
@@ -0,0 +1,521 @@
+//! RPC interface for the quadravote pallet.
+//!
+//! Every method is backed by the `QuadravoteApi` runtime API and takes an optional block hash,
+//! the best block is queried when it is omitted. Balances are returned as decimal strings so
+//! they survive JavaScript's number precision.
+//!
+//! `quadravote_subscribeEvents` pushes phase transitions, created and removed proposals and
+//! the final tallies of a voting period of every track for every new best block. They are
+//! found by comparing the runtime API state of the block with that of its parent, so reorgs
+//! are reported as they are imported.
+
+use std::{fmt::Display, marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::{
+		error::{CallError, ErrorObject},
+		SubscriptionResult,
+	},
+	SubscriptionSink,
+};
+use pallet_quadravote_runtime_api::{Phase as RuntimePhase, TrackId, VoteRecord, VotingProposal};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::{de::DeserializeOwned, Serialize, Serializer};
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::H256;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+
+pub use pallet_quadravote_runtime_api::QuadravoteApi as QuadravoteRuntimeApi;
+
+/// The phase the pallet is in.
+#[derive(Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+	Proposal,
+	Voting,
+	Cooldown,
+}
+
+impl From<RuntimePhase> for Phase {
+	fn from(phase: RuntimePhase) -> Self {
+		match phase {
+			RuntimePhase::Proposal => Phase::Proposal,
+			RuntimePhase::Voting => Phase::Voting,
+			RuntimePhase::Cooldown => Phase::Cooldown,
+		}
+	}
+}
+
+/// A track and its name.
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Track {
+	pub id: TrackId,
+	pub name: String,
+}
+
+impl From<(TrackId, Vec<u8>)> for Track {
+	fn from((id, name): (TrackId, Vec<u8>)) -> Self {
+		Track { id, name: String::from_utf8_lossy(&name).into_owned() }
+	}
+}
+
+/// The current phase of a track and the number of blocks until it ends.
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseInfo<BlockNumber> {
+	pub phase: Phase,
+	pub blocks_remaining: BlockNumber,
+}
+
+/// The live tally of a single proposal.
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalTally {
+	pub proposal: H256,
+	pub votes_for: u32,
+	pub votes_against: u32,
+}
+
+impl From<VotingProposal> for ProposalTally {
+	fn from(p: VotingProposal) -> Self {
+		ProposalTally {
+			proposal: p.proposal.into(),
+			votes_for: p.votes_for,
+			votes_against: p.votes_against,
+		}
+	}
+}
+
+/// The votes an account has cast on a single proposal in the current voting round.
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalVotes {
+	pub proposal: H256,
+	pub votes_for: u32,
+	pub votes_against: u32,
+}
+
+impl From<([u8; 32], VoteRecord)> for ProposalVotes {
+	fn from((proposal, votes): ([u8; 32], VoteRecord)) -> Self {
+		ProposalVotes { proposal: proposal.into(), votes_for: votes.aye, votes_against: votes.nay }
+	}
+}
+
+/// The votes of an account and the amount it has reserved for them.
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", bound(serialize = "Balance: Display"))]
+pub struct AccountVotes<Balance> {
+	pub votes: Vec<ProposalVotes>,
+	#[serde(serialize_with = "serialize_as_string")]
+	pub reserved: Balance,
+}
+
+/// The amount that would additionally be reserved by casting a vote.
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", bound(serialize = "Balance: Display"))]
+pub struct VoteQuote<Balance> {
+	#[serde(serialize_with = "serialize_as_string")]
+	pub cost: Balance,
+}
+
+/// A change in the state of a track, pushed to subscribers when the block that caused it
+/// is imported as the new best block.
+#[derive(Serialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum QuadravoteEvent<BlockHash> {
+	/// A voting period ended, `tallies` are the final tallies of its proposals.
+	#[serde(rename_all = "camelCase")]
+	VotingEnded { block_hash: BlockHash, track: TrackId, tallies: Vec<ProposalTally> },
+	/// A new phase started, or the track was registered.
+	#[serde(rename_all = "camelCase")]
+	PhaseChanged { block_hash: BlockHash, track: TrackId, phase: Phase },
+	/// A proposal was created.
+	#[serde(rename_all = "camelCase")]
+	ProposalCreated { block_hash: BlockHash, track: TrackId, proposal: H256 },
+	/// A proposal was removed before its voting period ended, either withdrawn by its
+	/// proposer or cancelled by the admin.
+	#[serde(rename_all = "camelCase")]
+	ProposalRemoved { block_hash: BlockHash, track: TrackId, proposal: H256 },
+}
+
+fn serialize_as_string<S: Serializer, T: Display>(t: &T, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&t.to_string())
+}
+
+#[rpc(server)]
+pub trait QuadravoteApi<BlockHash, AccountId, Balance, BlockNumber> {
+	/// Every track with its name, the default track first.
+	#[method(name = "quadravote_tracks")]
+	fn tracks(&self, at: Option<BlockHash>) -> RpcResult<Vec<Track>>;
+
+	/// The current phase of `track` and the number of blocks until it ends. `null` for an
+	/// unknown track.
+	#[method(name = "quadravote_phase")]
+	fn phase(
+		&self,
+		track: TrackId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<PhaseInfo<BlockNumber>>>;
+
+	/// Every proposal of the current round of `track` with its live tally.
+	#[method(name = "quadravote_proposals")]
+	fn proposals(&self, track: TrackId, at: Option<BlockHash>) -> RpcResult<Vec<ProposalTally>>;
+
+	/// The votes `who` has cast in the current voting round and the amount it has reserved.
+	#[method(name = "quadravote_accountVotes")]
+	fn account_votes(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<AccountVotes<Balance>>;
+
+	/// The amount `who` would additionally have reserved by casting `votes_for` and
+	/// `votes_against` on `proposal`. `null` if the vote would be rejected.
+	#[method(name = "quadravote_quoteVote")]
+	fn quote_vote(
+		&self,
+		who: AccountId,
+		proposal: H256,
+		votes_for: u32,
+		votes_against: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<VoteQuote<Balance>>>;
+
+	/// Subscribe to phase transitions, created and removed proposals and final tallies of
+	/// every track.
+	#[subscription(
+		name = "quadravote_subscribeEvents" => "quadravote_event",
+		unsubscribe = "quadravote_unsubscribeEvents",
+		item = QuadravoteEvent<BlockHash>,
+	)]
+	fn subscribe_events(&self);
+}
+
+/// Provides RPC methods to query the state of the quadravote pallet.
+pub struct Quadravote<C, Block> {
+	client: Arc<C>,
+	executor: SubscriptionTaskExecutor,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Quadravote<C, Block> {
+	/// Create a new `Quadravote` with the given reference to the client and the executor
+	/// subscriptions are run on.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl Display) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(Error::RuntimeError.into(), message, Some(e.to_string())))
+		.into()
+}
+
+#[async_trait]
+impl<C, Block, AccountId, Balance, BlockNumber>
+	QuadravoteApiServer<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber>
+	for Quadravote<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ BlockchainEvents<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: QuadravoteRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec + Clone + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Display + Serialize + Send + Sync + 'static,
+	BlockNumber: Codec + Serialize + Send + Sync + 'static,
+{
+	fn tracks(&self, at: Option<Block::Hash>) -> RpcResult<Vec<Track>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let tracks = api.tracks(&at).map_err(|e| runtime_error("Unable to query tracks.", e))?;
+		Ok(tracks.into_iter().map(Into::into).collect())
+	}
+
+	fn phase(
+		&self,
+		track: TrackId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<PhaseInfo<BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let phase =
+			api.phase(&at, track).map_err(|e| runtime_error("Unable to query phase.", e))?;
+		let blocks_remaining = api
+			.blocks_remaining(&at, track)
+			.map_err(|e| runtime_error("Unable to query blocks remaining.", e))?;
+		Ok(phase
+			.zip(blocks_remaining)
+			.map(|(phase, blocks_remaining)| PhaseInfo { phase: phase.into(), blocks_remaining }))
+	}
+
+	fn proposals(&self, track: TrackId, at: Option<Block::Hash>) -> RpcResult<Vec<ProposalTally>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let tallies = api
+			.tallies(&at, track)
+			.map_err(|e| runtime_error("Unable to query proposals.", e))?;
+		Ok(tallies.into_iter().map(Into::into).collect())
+	}
+
+	fn account_votes(
+		&self,
+		who: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<AccountVotes<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let votes = api
+			.votes_of(&at, who.clone())
+			.map_err(|e| runtime_error("Unable to query account votes.", e))?;
+		let reserved = api
+			.reserve_of(&at, who)
+			.map_err(|e| runtime_error("Unable to query account reserve.", e))?;
+		Ok(AccountVotes { votes: votes.into_iter().map(Into::into).collect(), reserved })
+	}
+
+	fn quote_vote(
+		&self,
+		who: AccountId,
+		proposal: H256,
+		votes_for: u32,
+		votes_against: u32,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<VoteQuote<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let cost = api
+			.quote_vote(&at, who, proposal.into(), votes_for, votes_against)
+			.map_err(|e| runtime_error("Unable to quote vote.", e))?;
+		Ok(cost.map(|cost| VoteQuote { cost }))
+	}
+
+	fn subscribe_events(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+		let client = self.client.clone();
+		let stream = self
+			.client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best))
+			.flat_map(move |notification| {
+				let events = match events_at::<_, _, AccountId, Balance, BlockNumber>(
+					&*client,
+					&notification.header,
+				) {
+					Ok(events) => events,
+					Err(e) => {
+						log::debug!(
+							target: "rpc",
+							"Unable to query quadravote state at {}: {}",
+							notification.hash,
+							e,
+						);
+						Vec::new()
+					},
+				};
+				stream::iter(events)
+			});
+
+		let fut = async move {
+			sink.pipe_from_stream(stream).await;
+		};
+		self.executor.spawn("quadravote-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
+
+/// The changes `header` made to the state of every track, compared to its parent.
+fn events_at<C, Block, AccountId, Balance, BlockNumber>(
+	client: &C,
+	header: &Block::Header,
+) -> Result<Vec<QuadravoteEvent<Block::Hash>>, ApiError>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: QuadravoteRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+{
+	let api = client.runtime_api();
+	let at = BlockId::hash(header.hash());
+	let parent = BlockId::hash(*header.parent_hash());
+	let mut events = Vec::new();
+	// A removed track has no proposals left, there is nothing to report for it.
+	for (track, _) in api.tracks(&at)? {
+		let phase_after = match api.phase(&at, track)? {
+			Some(phase) => phase,
+			None => continue,
+		};
+		events.extend(changes(
+			header.hash(),
+			track,
+			(api.phase(&parent, track)?, api.tallies(&parent, track)?),
+			(phase_after, api.tallies(&at, track)?),
+		));
+	}
+	Ok(events)
+}
+
+/// The events describing the step of `track` from the `before` to the `after` phase and
+/// proposals. The phase before is `None` if the track was registered in between.
+fn changes<BlockHash: Copy>(
+	block_hash: BlockHash,
+	track: TrackId,
+	(phase_before, proposals_before): (Option<RuntimePhase>, Vec<VotingProposal>),
+	(phase_after, proposals_after): (RuntimePhase, Vec<VotingProposal>),
+) -> Vec<QuadravoteEvent<BlockHash>> {
+	let mut events = Vec::new();
+	let mut proposals_before = proposals_before;
+	if phase_before != Some(phase_after) {
+		if phase_before == Some(RuntimePhase::Voting) {
+			// The proposals are cleared when the voting period ends, what they held in the
+			// parent block are their final tallies.
+			let tallies = proposals_before.drain(..).map(Into::into).collect();
+			events.push(QuadravoteEvent::VotingEnded { block_hash, track, tallies });
+		}
+		events.push(QuadravoteEvent::PhaseChanged { block_hash, track, phase: phase_after.into() });
+	}
+
+	for p in proposals_before.iter() {
+		if !proposals_after.iter().any(|after| after.proposal == p.proposal) {
+			events.push(QuadravoteEvent::ProposalRemoved {
+				block_hash,
+				track,
+				proposal: p.proposal.into(),
+			});
+		}
+	}
+	for p in proposals_after.iter() {
+		if !proposals_before.iter().any(|before| before.proposal == p.proposal) {
+			events.push(QuadravoteEvent::ProposalCreated {
+				block_hash,
+				track,
+				proposal: p.proposal.into(),
+			});
+		}
+	}
+	events
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn proposal(id: u8, votes_for: u32, votes_against: u32) -> VotingProposal {
+		VotingProposal { proposal: [id; 32], votes_for, votes_against }
+	}
+
+	#[test]
+	fn changes_within_a_phase() {
+		let events = changes(
+			0u8,
+			1,
+			(Some(RuntimePhase::Proposal), vec![proposal(0, 0, 0), proposal(1, 0, 0)]),
+			(RuntimePhase::Proposal, vec![proposal(1, 0, 0), proposal(2, 0, 0)]),
+		);
+		assert_eq!(
+			events,
+			vec![
+				QuadravoteEvent::ProposalRemoved {
+					block_hash: 0,
+					track: 1,
+					proposal: [0; 32].into()
+				},
+				QuadravoteEvent::ProposalCreated {
+					block_hash: 0,
+					track: 1,
+					proposal: [2; 32].into()
+				},
+			]
+		);
+
+		// Votes being cast are not reported
+		let events = changes(
+			0u8,
+			1,
+			(Some(RuntimePhase::Voting), vec![proposal(0, 0, 0)]),
+			(RuntimePhase::Voting, vec![proposal(0, 3, 1)]),
+		);
+		assert!(events.is_empty());
+	}
+
+	#[test]
+	fn changes_at_the_end_of_voting() {
+		// Proposals can be created in the block the voting period ends in
+		let events = changes(
+			0u8,
+			1,
+			(Some(RuntimePhase::Voting), vec![proposal(0, 3, 1)]),
+			(RuntimePhase::Proposal, vec![proposal(1, 0, 0)]),
+		);
+		assert_eq!(
+			events,
+			vec![
+				QuadravoteEvent::VotingEnded {
+					block_hash: 0,
+					track: 1,
+					tallies: vec![proposal(0, 3, 1).into()],
+				},
+				QuadravoteEvent::PhaseChanged { block_hash: 0, track: 1, phase: Phase::Proposal },
+				QuadravoteEvent::ProposalCreated {
+					block_hash: 0,
+					track: 1,
+					proposal: [1; 32].into()
+				},
+			]
+		);
+
+		// The proposals are carried over into the voting period
+		let events = changes(
+			0u8,
+			1,
+			(Some(RuntimePhase::Proposal), vec![proposal(1, 0, 0)]),
+			(RuntimePhase::Voting, vec![proposal(1, 0, 0)]),
+		);
+		assert_eq!(
+			events,
+			vec![QuadravoteEvent::PhaseChanged { block_hash: 0, track: 1, phase: Phase::Voting }]
+		);
+	}
+
+	#[test]
+	fn changes_of_a_new_track() {
+		let events = changes(0u8, 1, (None, vec![]), (RuntimePhase::Proposal, vec![]));
+		assert_eq!(
+			events,
+			vec![QuadravoteEvent::PhaseChanged { block_hash: 0, track: 1, phase: Phase::Proposal }]
+		);
+	}
+}
@@ -0,0 +1,44 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Runtime API for querying the state of the quadravote pallet without decoding its storage.
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_quadravote::{Phase, TrackId, VoteRecord, VotingProposal};
+
+sp_api::decl_runtime_apis! {
+	pub trait QuadravoteApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Every track with its name, the default track first.
+		fn tracks() -> Vec<(TrackId, Vec<u8>)>;
+
+		/// The phase `track` is currently in, `None` for an unknown track.
+		fn phase(track: TrackId) -> Option<Phase>;
+
+		/// The number of blocks until the current phase of `track` ends, `None` for an
+		/// unknown track.
+		fn blocks_remaining(track: TrackId) -> Option<BlockNumber>;
+
+		/// The live tally of every proposal of `track` in the current round.
+		fn tallies(track: TrackId) -> Vec<VotingProposal>;
+
+		/// The votes `who` has cast in the current voting round of every track, per proposal.
+		fn votes_of(who: AccountId) -> Vec<([u8; 32], VoteRecord)>;
+
+		/// The amount `who` has reserved for votes, including any reserve not yet refunded.
+		fn reserve_of(who: AccountId) -> Balance;
+
+		/// The amount `who` would additionally have reserved by casting `votes_for` and
+		/// `votes_against` on `proposal`, or `None` if the vote would be rejected.
+		fn quote_vote(
+			who: AccountId,
+			proposal: [u8; 32],
+			votes_for: u32,
+			votes_against: u32,
+		) -> Option<Balance>;
+	}
+}
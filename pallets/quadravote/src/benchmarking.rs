@@ -1,20 +1,344 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-quadravote
 
 use super::*;
 
-#[allow(unused)]
-use crate::Pallet as Template;
-use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use crate::Pallet as Quadravote;
+use codec::Encode;
+use corpus_traits::IdentityInterface;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::{
+	traits::{Currency, EnsureOrigin, Get, Hooks},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
+use sp_runtime::{
+	traits::{Bounded, Saturating},
+	Perbill,
+};
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+
+/// An identified account with enough free balance to cast any number of votes.
+fn identified_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	T::IdentityProvider::ensure_identified(&who);
+	who
+}
+
+/// Metadata of the maximum size, carrying a remark of the maximum size that is distinct for
+/// every index. Returns the metadata and the proposal hash of the call.
+fn max_metadata<T: Config>(
+	index: u32,
+) -> (
+	[u8; 32],
+	BoundedVec<u8, T::MaxTitleLength>,
+	BoundedVec<u8, T::MaxDescriptionUriLength>,
+	BoundedVec<u8, T::MaxCallLength>,
+) {
+	let title = BoundedVec::truncate_from(vec![b't'; T::MaxTitleLength::get() as usize]);
+	let uri = BoundedVec::truncate_from(vec![b'u'; T::MaxDescriptionUriLength::get() as usize]);
+	// Leave room for the call indices and the length prefix of the remark.
+	let mut remark = vec![0u8; (T::MaxCallLength::get() as usize).saturating_sub(8)];
+	for (byte, index_byte) in remark.iter_mut().zip(index.to_le_bytes()) {
+		*byte = index_byte;
+	}
+	let call: <T as Config>::Proposal = frame_system::Call::<T>::remark { remark }.into();
+	let call = BoundedVec::truncate_from(call.encode());
+	(sp_io::hashing::blake2_256(&call), title, uri, call)
+}
+
+/// Enter the proposal period and create `n` proposals with metadata of the maximum size,
+/// each by its own proposer. Returns the proposal hashes.
+fn create_proposals<T: Config>(n: u32) -> Result<Vec<[u8; 32]>, &'static str> {
+	ProposalPeriod::<T>::insert(DEFAULT_TRACK, ());
+	let mut proposals = Vec::new();
+	for i in 0..n {
+		let proposer = identified_account::<T>("proposer", i);
+		let (proposal, title, uri, call) = max_metadata::<T>(i);
+		Quadravote::<T>::create_proposal(
+			RawOrigin::Signed(proposer).into(),
+			DEFAULT_TRACK,
+			proposal,
+			title,
+			uri,
+			Some(call),
+		)?;
+		proposals.push(proposal);
+	}
+	Ok(proposals)
+}
+
+/// A non-zero contribution that the beneficiary can receive.
+fn min_contribution<T: Config>() -> BalanceOf<T> {
+	T::Currency::minimum_balance().saturating_add(1u32.into())
+}
+
+/// Create `n` funding proposals and enter the voting period, then have `c` contributors
+/// contribute to each of them. Returns the proposal hashes.
+fn create_funding_proposals<T: Config>(n: u32, c: u32) -> Result<Vec<[u8; 32]>, &'static str> {
+	ProposalPeriod::<T>::insert(DEFAULT_TRACK, ());
+	let mut proposals = Vec::new();
+	for i in 0..n {
+		let proposer = identified_account::<T>("proposer", i);
+		let beneficiary: T::AccountId = account("beneficiary", i, SEED);
+		let (proposal, title, uri, _) = max_metadata::<T>(i);
+		Quadravote::<T>::create_funding_proposal(
+			RawOrigin::Signed(proposer).into(),
+			DEFAULT_TRACK,
+			proposal,
+			title,
+			uri,
+			beneficiary,
+		)?;
+		proposals.push(proposal);
+	}
+	ProposalPeriod::<T>::remove(DEFAULT_TRACK);
+	for proposal in proposals.iter() {
+		for i in 0..c {
+			let contributor = identified_account::<T>("contributor", i);
+			let amount = min_contribution::<T>().saturating_mul((i + 1).into());
+			Quadravote::<T>::contribute(RawOrigin::Signed(contributor).into(), *proposal, amount)?;
+		}
+	}
+	Ok(proposals)
+}
+
+/// Have `v` voters each cast a single vote, spread over `proposals`.
+fn cast_votes<T: Config>(proposals: &[[u8; 32]], v: u32) -> Result<(), &'static str> {
+	for i in 0..v {
+		let voter = identified_account::<T>("voter", i);
+		let proposal = proposals[i as usize % proposals.len()];
+		Quadravote::<T>::cast_vote(RawOrigin::Signed(voter).into(), proposal, 1, 0)?;
+	}
+	Ok(())
+}
 
 benchmarks! {
-	do_something {
-		let s in 0 .. 100;
-		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+	create_proposal {
+		let p in 0 .. T::MaxProposals::get() - 1;
+		create_proposals::<T>(p)?;
+		let caller = identified_account::<T>("caller", 0);
+		let (proposal, title, uri, call) = max_metadata::<T>(p);
+	}: _(RawOrigin::Signed(caller), DEFAULT_TRACK, proposal, title, uri, Some(call))
+	verify {
+		assert!(CountedProposals::<T>::contains_key(proposal));
+	}
+
+	withdraw_proposal {
+		let p in 1 .. T::MaxProposals::get();
+		let proposals = create_proposals::<T>(p)?;
+		// Withdraw the last proposal so all of `Proposals` has to be searched.
+		let caller: T::AccountId = account("proposer", p - 1, SEED);
+		let proposal = proposals[(p - 1) as usize];
+	}: _(RawOrigin::Signed(caller), proposal)
+	verify {
+		assert!(!CountedProposals::<T>::contains_key(proposal));
+	}
+
+	cast_vote {
+		let p in 1 .. T::MaxProposals::get();
+		let proposals = create_proposals::<T>(p)?;
+		ProposalPeriod::<T>::remove(DEFAULT_TRACK);
+		let caller = identified_account::<T>("caller", 0);
+		let proposal = proposals[(p - 1) as usize];
+		let votes = T::MaxVotesPerAccount::get();
+	}: _(RawOrigin::Signed(caller.clone()), proposal, votes, 0)
+	verify {
+		assert!(Voters::<T>::contains_key(DEFAULT_TRACK, &caller));
+	}
+
+	adjust_vote {
+		let p in 1 .. T::MaxProposals::get();
+		let proposals = create_proposals::<T>(p)?;
+		ProposalPeriod::<T>::remove(DEFAULT_TRACK);
+		let caller = identified_account::<T>("caller", 0);
+		let proposal = proposals[(p - 1) as usize];
+		let votes = T::MaxVotesPerAccount::get();
+		Quadravote::<T>::cast_vote(RawOrigin::Signed(caller.clone()).into(), proposal, votes, 0)?;
+	}: _(RawOrigin::Signed(caller.clone()), proposal, 0, 0)
+	verify {
+		assert_eq!(Proposals::<T>::get(DEFAULT_TRACK).unwrap()[(p - 1) as usize].votes_for, 0);
+	}
+
+	on_initialize_idle {
+		let block: T::BlockNumber = 1u32.into();
+		PhaseStart::<T>::insert(DEFAULT_TRACK, block);
+	}: {
+		Quadravote::<T>::on_initialize(block);
+	}
+
+	on_initialize_end_proposal_period {
+		ProposalPeriod::<T>::insert(DEFAULT_TRACK, ());
+		let block: T::BlockNumber = T::ProposalPeriodLength::get().into();
+	}: {
+		Quadravote::<T>::on_initialize(block);
+	}
+	verify {
+		assert!(!ProposalPeriod::<T>::contains_key(DEFAULT_TRACK));
+	}
+
+	on_initialize_end_voting_period {
+		let p in 1 .. T::MaxProposals::get();
+		let v in 0 .. T::MaxRefundsPerBlock::get().min(T::MaxVotersPerSession::get());
+		let proposals = create_proposals::<T>(p)?;
+		ProposalPeriod::<T>::remove(DEFAULT_TRACK);
+		cast_votes::<T>(&proposals, v)?;
+		// Every proposal reaches the quorum and wins, so all of them are scheduled.
+		Proposals::<T>::mutate(DEFAULT_TRACK, |maybe_proposals| {
+			for p in maybe_proposals.iter_mut().flat_map(|ps| ps.iter_mut()) {
+				p.votes_for = T::MinimumTurnout::get().max(1);
+				p.votes_against = 0;
+				ProposalVoters::<T>::insert(p.proposal, T::MinimumVoters::get());
+			}
+		});
+		let block: T::BlockNumber = T::VotingPeriodLength::get().into();
+	}: {
+		Quadravote::<T>::on_initialize(block);
+	}
+	verify {
+		assert!(ProposalPeriod::<T>::contains_key(DEFAULT_TRACK));
+		assert_eq!(Voters::<T>::iter_prefix(DEFAULT_TRACK).count(), 0);
+	}
+
+	claim_refund {
+		let proposals = create_proposals::<T>(1)?;
+		ProposalPeriod::<T>::remove(DEFAULT_TRACK);
+		cast_votes::<T>(&proposals, 1)?;
+		// Voting has ended but nobody has been refunded yet.
+		VotingRound::<T>::insert(DEFAULT_TRACK, 1);
+		let voter: T::AccountId = account("voter", 0, SEED);
+		let caller = identified_account::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller), DEFAULT_TRACK, voter.clone())
+	verify {
+		assert!(!Voters::<T>::contains_key(DEFAULT_TRACK, &voter));
+	}
+
+	enact {
+		let (proposal, _, _, call) = max_metadata::<T>(0);
+		let when = frame_system::Pallet::<T>::block_number();
+		PendingEnactments::<T>::insert(
+			proposal,
+			PendingEnactment { when, call: Some(call), repeals: None },
+		);
+	}: _(RawOrigin::Root, proposal)
+	verify {
+		assert!(EnactedProposals::<T>::contains_key(proposal));
+	}
+
+	propose_repeal {
+		let p in 0 .. T::MaxProposals::get() - 1;
+		create_proposals::<T>(p)?;
+		let target = [0xffu8; 32];
+		EnactedProposals::<T>::insert(target, ());
+		let caller = identified_account::<T>("caller", 0);
+		let (_, title, uri, _) = max_metadata::<T>(p);
+	}: _(RawOrigin::Signed(caller), DEFAULT_TRACK, target, title, uri)
+	verify {
+		assert!(CountedProposals::<T>::contains_key(Quadravote::<T>::repeal_hash(&target)));
+	}
+
+	create_funding_proposal {
+		let p in 0 .. T::MaxProposals::get() - 1;
+		create_proposals::<T>(p)?;
+		let caller = identified_account::<T>("caller", 0);
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let (proposal, title, uri, _) = max_metadata::<T>(p);
+	}: _(RawOrigin::Signed(caller), DEFAULT_TRACK, proposal, title, uri, beneficiary)
+	verify {
+		assert!(FundingOf::<T>::contains_key(proposal));
+	}
+
+	contribute {
+		let c in 0 .. T::MaxContributors::get() - 1;
+		let proposals = create_funding_proposals::<T>(1, c)?;
+		let caller = identified_account::<T>("caller", 0);
+		let amount = min_contribution::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), proposals[0], amount)
+	verify {
+		let funding = FundingOf::<T>::get(proposals[0]).unwrap();
+		assert!(funding.contributions.iter().any(|(who, _)| *who == caller));
+	}
+
+	on_initialize_distribute_matching {
+		let p in 0 .. T::MaxProposals::get();
+		let proposals = create_funding_proposals::<T>(p, T::MaxContributors::get())?;
+		let pool = Quadravote::<T>::matching_pool_account(DEFAULT_TRACK);
+		T::Currency::make_free_balance_be(&pool, BalanceOf::<T>::max_value() / 2u32.into());
+		let proposals = Proposals::<T>::get(DEFAULT_TRACK).unwrap_or_default();
+	}: {
+		Quadravote::<T>::distribute_matching(DEFAULT_TRACK, &proposals);
+	}
+	verify {
+		assert_eq!(FundingOf::<T>::iter().count(), 0);
+	}
+
+	cancel_proposal {
+		let v in 0 .. T::MaxVotersPerSession::get();
+		let proposals = create_proposals::<T>(1)?;
+		ProposalPeriod::<T>::remove(DEFAULT_TRACK);
+		cast_votes::<T>(&proposals, v)?;
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, proposals[0])
+	verify {
+		assert!(!CountedProposals::<T>::contains_key(proposals[0]));
+	}
+
+	pause {
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin)
+	verify {
+		assert!(Paused::<T>::exists());
+	}
+
+	resume {
+		let t in 1 .. T::MaxTracks::get() + 1;
+		for track in 1..t {
+			Tracks::<T>::insert(track as TrackId, Quadravote::<T>::default_track_info());
+		}
+		Paused::<T>::put(T::BlockNumber::from(0u32));
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin)
+	verify {
+		assert!(!Paused::<T>::exists());
+	}
+
+	register_track {
+		let origin = T::AdminOrigin::successful_origin();
+		let mut info = Quadravote::<T>::default_track_info();
+		info.name = BoundedVec::truncate_from(vec![b'n'; T::MaxTrackNameLength::get() as usize]);
+		info.threshold = Some(ThresholdRule::SuperMajority(Perbill::from_percent(66)));
+	}: _<T::Origin>(origin, 1, info)
+	verify {
+		assert!(ProposalPeriod::<T>::contains_key(1));
+	}
+
+	remove_track {
+		Tracks::<T>::insert(1, Quadravote::<T>::default_track_info());
+		ProposalPeriod::<T>::insert(1, ());
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, 1)
+	verify {
+		assert!(!Tracks::<T>::contains_key(1));
+	}
+
+	on_initialize_refund_voters {
+		let v in 0 .. T::MaxRefundsPerBlock::get().min(T::MaxVotersPerSession::get());
+		let proposals = create_proposals::<T>(1)?;
+		ProposalPeriod::<T>::remove(DEFAULT_TRACK);
+		cast_votes::<T>(&proposals, v)?;
+		// Voting has ended but nobody has been refunded yet.
+		VotingRound::<T>::insert(DEFAULT_TRACK, 1);
+		ProposalPeriod::<T>::insert(DEFAULT_TRACK, ());
+		let block: T::BlockNumber = 1u32.into();
+		PhaseStart::<T>::insert(DEFAULT_TRACK, block);
+	}: {
+		Quadravote::<T>::on_initialize(block);
+	}
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert_eq!(Voters::<T>::iter_prefix(DEFAULT_TRACK).count(), 0);
 	}
 }
 
-impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test,);
+impl_benchmark_test_suite!(Quadravote, crate::mock::new_test_ext(), crate::mock::Test,);
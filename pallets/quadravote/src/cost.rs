@@ -0,0 +1,88 @@
+//! How voters pay for the votes they cast.
+//!
+//! Every voter has a budget to spend on votes in each voting round of a track. With
+//! `ReserveFunds` the budget is the `max_votes_per_account` of the track, every vote spends
+//! one of it and the voter reserves `VoteCostUnit` times the cost of their votes on a
+//! proposal until the round is over. With `VoiceCredits` the budget is the voice credits the
+//! identity provider issues to the voter, capped by the `max_votes_per_account` of the track,
+//! votes on a proposal spend their cost in credits and nothing is reserved. The cost of `n`
+//! votes is set by the `VoteCostFunction` of the pallet, `n * n` for `Quadratic`.
+
+use corpus_traits::IdentityInterface;
+pub use corpus_traits::{Linear, Power, Quadratic, VoteCostFunction};
+
+/// How voters pay for their votes, see the module documentation.
+pub trait VoteCostModel<AccountId> {
+	/// Whether votes are paid for by reserving funds of the voter.
+	fn reserves_funds() -> bool;
+
+	/// The budget `who` can spend on votes in a single voting round of a track that allows
+	/// `max_votes_per_account` votes per account.
+	fn budget(who: &AccountId, max_votes_per_account: u32) -> u32;
+
+	/// The part of the budget spent by holding `votes` votes on a single proposal, when
+	/// votes are priced by `Cost`. `None` on overflow.
+	fn spend<Cost: VoteCostFunction>(votes: u32) -> Option<u32>;
+
+	/// The most votes a single account can hold on a proposal of a track that allows
+	/// `max_votes_per_account` votes per account, when votes are priced by `Cost`.
+	fn max_votes<Cost: VoteCostFunction>(max_votes_per_account: u32) -> u32;
+}
+
+/// Votes reserve funds and are capped by the `max_votes_per_account` of the track.
+pub struct ReserveFunds;
+
+impl<AccountId> VoteCostModel<AccountId> for ReserveFunds {
+	fn reserves_funds() -> bool {
+		true
+	}
+
+	fn budget(_who: &AccountId, max_votes_per_account: u32) -> u32 {
+		max_votes_per_account
+	}
+
+	fn spend<Cost: VoteCostFunction>(votes: u32) -> Option<u32> {
+		Some(votes)
+	}
+
+	fn max_votes<Cost: VoteCostFunction>(max_votes_per_account: u32) -> u32 {
+		max_votes_per_account
+	}
+}
+
+/// Votes are paid for out of the voice credits `Issuer` hands every identified voter for
+/// each round, at most the `max_votes_per_account` of the track. No funds are reserved.
+pub struct VoiceCredits<Issuer>(sp_std::marker::PhantomData<Issuer>);
+
+impl<AccountId, Issuer: IdentityInterface<AccountId>> VoteCostModel<AccountId>
+	for VoiceCredits<Issuer>
+{
+	fn reserves_funds() -> bool {
+		false
+	}
+
+	fn budget(who: &AccountId, max_votes_per_account: u32) -> u32 {
+		Issuer::voice_credits(who).min(max_votes_per_account)
+	}
+
+	fn spend<Cost: VoteCostFunction>(votes: u32) -> Option<u32> {
+		Cost::cost(votes).and_then(|cost| cost.try_into().ok())
+	}
+
+	fn max_votes<Cost: VoteCostFunction>(max_votes_per_account: u32) -> u32 {
+		// The cost is non-decreasing, search for the most votes the budget still covers
+		let affordable = |votes| {
+			<Self as VoteCostModel<AccountId>>::spend::<Cost>(votes)
+				.map_or(false, |spent| spent <= max_votes_per_account)
+		};
+		let (mut low, mut high) = (0u32, u32::MAX);
+		while low < high {
+			let mid = low + (high - low) / 2 + 1;
+			match affordable(mid) {
+				true => low = mid,
+				false => high = mid - 1,
+			}
+		}
+		low
+	}
+}
@@ -15,25 +15,46 @@
 //! The implementation splits time up in two periods. First, a proposal period
 //! where identified account holders can bring up a proposal for voting.
 //! Then a voting period where identified account holders can reserve an amount
-//! of their tokens to vote on a particular subject.
+//! of their tokens to vote on a particular subject. An optional cool-down period
+//! separates a voting period from the next proposal period.
 //!
 //! A proposer can withdraw their proposal if it is still the same proposal round
 //!
 //! ### Terminology:
 //!
-//! A period: either voting or proposing takes place within a period. The period for voting
-//! is exactly as long as the period for proposing. This is due to the fact that the current
-//! naive selection of pallets is the modulus of the length of a period.
+//! A track: a named queue of proposals running its own cycle of periods, so different kinds
+//! of decisions do not compete with each other. Every track has its own period lengths,
+//! proposal limit, vote budget, quorum and threshold, see `TrackInfo`. The default track,
+//! `DEFAULT_TRACK`, runs with the parameters set in `Config` unless the admin registers it
+//! with its own; further tracks are registered by the admin in `Tracks`.
+//!
+//! A period: either voting or proposing takes place within a period. Each period runs for
+//! its own length, `TrackInfo::proposal_period_length`, `TrackInfo::voting_period_length` and
+//! `TrackInfo::cooldown_period_length`, counted from the block it started in.
 //!
 //! An identified account holder is an account holder that has registered to vote
 //! via the `pallet-votingregistry` pallet VotingRegistry module.
 //!
-//! A vote is the square root of the amount of tokens reserved.
+//! A vote is the square root of the amount of tokens reserved, measured in `Config::VoteCostUnit`,
+//! when votes are priced by `Quadratic`. `Config::VoteCostFunction` sets the price curve.
 //!
 //! Storage:
-//! A ProposalPeriod 'boolean' is stored to denote if we are in the voting period or the proposal period.
-//! Every proposal is stored in CountedProposals, a CountedStorageMap that is limited in size
-//! per round (defined in Config::MaxProposals).
+//! A ProposalPeriod 'boolean' is stored per track to denote if it is in the voting period or the
+//! proposal period, a CooldownPeriod 'boolean' whether the proposal period has yet to start.
+//! PhaseStart holds the block the current period of the track started in.
+//! Every proposal is stored in CountedProposals, a CountedStorageMap, and in the Proposals of its
+//! track, which are limited in size per round (defined in TrackInfo::max_proposals).
+//! The title, description URI and optional call of every live proposal are stored in MetadataOf,
+//! backed by a deposit from the proposer.
+//!
+//! A proposal wins when it reaches the quorum set by the `minimum_turnout` and
+//! `minimum_voters` of its track and passes the threshold of its track, see `threshold`.
+//!
+//! Funding proposals have a beneficiary that identified accounts contribute to during the
+//! voting period. When the period ends the matching pool of the track, see
+//! `matching_pool_account`, is shared between the funding proposals of the track by quadratic
+//! funding: a proposal's match is the square of the sum of the square roots of its
+//! contributions, minus the contributions themselves.
 pub use pallet::*;
 
 #[cfg(test)]
@@ -45,24 +66,50 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod cost;
+pub mod migrations;
+pub mod threshold;
+pub mod weights;
+pub use cost::*;
+pub use threshold::*;
+pub use weights::*;
+
 #[frame_support::pallet]
 pub mod pallet {
-	use corpus_traits::IdentityInterface;
+	use crate::{
+		ApprovalThreshold, Outcome, SelectionMode, Tally, ThresholdRule, VoteCostFunction,
+		VoteCostModel, WeightInfo,
+	};
+	use corpus_traits::{IdentityInUse, IdentityInterface};
 	use frame_support::{
-		dispatch::DispatchResult,
+		dispatch::{DispatchResult, Dispatchable, GetDispatchInfo, PostDispatchInfo},
 		fail,
 		inherent::Vec,
 		pallet_prelude::*,
-		traits::{Currency, ReservableCurrency},
-		BoundedVec,
+		traits::{
+			schedule::{DispatchTime, Named as ScheduleNamed},
+			Contains, Currency, ExistenceRequirement, OnUnbalanced, ReservableCurrency,
+		},
+		weights::extract_actual_weight,
+		BoundedVec, CloneNoBound, EqNoBound, PalletId, PartialEqNoBound, RuntimeDebugNoBound,
 	};
 	use frame_system::{
-		ensure_signed,
+		ensure_root, ensure_signed,
 		pallet_prelude::{BlockNumberFor, *},
 	};
+	use sp_runtime::{
+		traits::{
+			AccountIdConversion, CheckedAdd, CheckedMul, CheckedSub, IntegerSquareRoot, Saturating,
+			Zero,
+		},
+		Perbill,
+	};
 
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
@@ -72,82 +119,447 @@ pub mod pallet {
 		type Currency: ReservableCurrency<Self::AccountId>;
 		type IdentityProvider: IdentityInterface<Self::AccountId>;
 
-		/// How many blocks does each period run for.
+		/// How many blocks the proposal period of the default track runs for.
+		#[pallet::constant]
+		type ProposalPeriodLength: Get<u32>;
+
+		/// How many blocks the voting period of the default track runs for.
 		#[pallet::constant]
+		type VotingPeriodLength: Get<u32>;
 
-		type PeriodLength: Get<u32>;
-		/// How many proposals can run simultaneously.
+		/// How many blocks the default track waits between the end of a voting period and
+		/// the start of the next proposal period. Zero for no cool-down.
 		#[pallet::constant]
+		type CooldownPeriodLength: Get<u32>;
 
+		/// How many proposals can run simultaneously on the default track. No track can
+		/// run more than this.
+		#[pallet::constant]
 		type MaxProposals: Get<u32>;
-		/// How many votes can be cast for or against a proposal by a single account.
+
+		/// How many votes can be cast for or against the proposals of the default track by
+		/// a single account.
 		#[pallet::constant]
 		type MaxVotesPerAccount: Get<u32>;
 
-		/// How many voters can participate in a single voting period.
+		/// The amount reserved per unit of vote cost, `n` votes on a proposal reserve
+		/// `VoteCostFunction::cost(n) * VoteCostUnit`. Only used when `CostModel` reserves
+		/// funds.
+		#[pallet::constant]
+		type VoteCostUnit: Get<BalanceOf<Self>>;
+
+		/// Prices the votes an account holds on a single proposal, `Quadratic` for
+		/// quadratic voting.
+		type VoteCostFunction: VoteCostFunction;
+
+		/// How voters pay for their votes: `ReserveFunds` to reserve `VoteCostUnit` per
+		/// unit of vote cost up to `MaxVotesPerAccount` votes, or `VoiceCredits` to spend the
+		/// voice credits issued by the identity provider without reserving anything. With
+		/// `VoiceCredits` the `max_votes_per_account` of a track caps the credits spent on it.
+		type CostModel: VoteCostModel<Self::AccountId>;
+
+		/// How many voters can participate in a single voting period of a track.
 		/// This is of course not very democratic but there's a tradeoff
 		/// to be made and by having the bound, it is possible to make
 		/// decisions informed by benchmarking later.
+		/// Refunds are made lazily so this bound does not need to fit in a block.
 		#[pallet::constant]
 		type MaxVotersPerSession: Get<u32>;
+
+		/// How many voters are refunded in a single block outside of the voting period.
+		/// Voters that are not swept before the next voting period begins keep their
+		/// reserve until they claim it with `claim_refund` or vote again.
+		#[pallet::constant]
+		type MaxRefundsPerBlock: Get<u32>;
+
+		/// The maximum length of a proposal title, in bytes.
+		#[pallet::constant]
+		type MaxTitleLength: Get<u32>;
+
+		/// The maximum length of the URI pointing to a proposal's description, in bytes.
+		#[pallet::constant]
+		type MaxDescriptionUriLength: Get<u32>;
+
+		/// The maximum length of the encoded call a proposal can carry, in bytes.
+		#[pallet::constant]
+		type MaxCallLength: Get<u32>;
+
+		/// The runtime call a proposal can carry, dispatched when the proposal is enacted.
+		type Proposal: Parameter
+			+ Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>
+			+ From<Call<Self>>;
+
+		/// The origin the call of a winning proposal is dispatched with.
+		type EnactmentOrigin: Get<frame_system::RawOrigin<Self::AccountId>>;
+
+		/// The calls a proposal may carry. Checked when the proposal is created and again
+		/// when it is enacted, in case a runtime upgrade changed the filter in between. With
+		/// a privileged `EnactmentOrigin` this keeps a handful of voters from dispatching
+		/// arbitrary calls with it.
+		type ProposalFilter: Contains<<Self as Config>::Proposal>;
+
+		/// The origin that can force the end of a phase, cancel proposals, pause the cycle and
+		/// register tracks.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The maximum weight of the call a proposal can carry. The winners of a round are
+		/// enacted in the same block, so `MaxProposals` times this must fit in a block.
+		/// Should several tracks end in the same block, the scheduler postpones what does
+		/// not fit.
+		#[pallet::constant]
+		type MaxCallWeight: Get<Weight>;
+
+		/// How many blocks after the end of its voting period a winning proposal is enacted.
+		/// Gives those who disagree with a decision time to react before it takes effect.
+		#[pallet::constant]
+		type EnactmentPeriod: Get<Self::BlockNumber>;
+
+		/// The fewest votes, for and against, a proposal on the default track needs before
+		/// it can win.
+		#[pallet::constant]
+		type MinimumTurnout: Get<u32>;
+
+		/// The fewest accounts that have to vote on a proposal on the default track before it
+		/// can win.
+		#[pallet::constant]
+		type MinimumVoters: Get<u32>;
+
+		/// Decides whether a proposal that reached the quorum wins, e.g. `SimpleMajority`.
+		/// Used by every track that does not set a threshold of its own.
+		type Threshold: ApprovalThreshold;
+
+		/// Whether every approved proposal on the default track wins or only the highest
+		/// ranked ones.
+		#[pallet::constant]
+		type Selection: Get<SelectionMode>;
+
+		/// The scheduler winning proposals are queued with until their enactment.
+		type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Proposal, Self::PalletsOrigin>;
+
+		/// The overarching origin type the scheduler dispatches with.
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+
+		/// The deposit reserved for every proposal's metadata.
+		#[pallet::constant]
+		type MetadataDepositBase: Get<BalanceOf<Self>>;
+
+		/// The deposit reserved per byte of a proposal's metadata.
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The deposit reserved for every proposal on top of its metadata deposit. Returned
+		/// once the proposal reaches the quorum, partially slashed if it is withdrawn or does
+		/// not reach the quorum.
+		#[pallet::constant]
+		type ProposalDeposit: Get<BalanceOf<Self>>;
+
+		/// The share of the proposal deposit that is slashed.
+		#[pallet::constant]
+		type DepositSlash: Get<Perbill>;
+
+		/// Handler for slashed proposal deposits.
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The id of the matching pool account funding proposals are matched from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The maximum number of accounts that can contribute to a funding proposal.
+		#[pallet::constant]
+		type MaxContributors: Get<u32>;
+
+		/// The maximum number of tracks in `Tracks`.
+		#[pallet::constant]
+		type MaxTracks: Get<u32>;
+
+		/// The maximum length of the name of a track, in bytes.
+		#[pallet::constant]
+		type MaxTrackNameLength: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The scheduler name prefix of enactments.
+	const ENACTMENT_ID: [u8; 8] = *b"quadvote";
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	/// The identifier of a track.
+	pub type TrackId = u16;
+
+	/// The track that runs with the parameters set in `Config`, unless it is registered in
+	/// `Tracks` with its own.
+	pub const DEFAULT_TRACK: TrackId = 0;
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
-	/// Determines if we are in the proposal or voting period.
+	/// The parameters a track runs its cycle with.
+	#[derive(
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct TrackInfo<T: Config> {
+		/// A human readable name for the track.
+		pub name: BoundedVec<u8, T::MaxTrackNameLength>,
+		/// How many blocks the proposal period runs for.
+		pub proposal_period_length: u32,
+		/// How many blocks the voting period runs for.
+		pub voting_period_length: u32,
+		/// How many blocks to wait between the end of a voting period and the start of the
+		/// next proposal period. Zero for no cool-down.
+		pub cooldown_period_length: u32,
+		/// How many proposals can run simultaneously, at most `Config::MaxProposals`.
+		pub max_proposals: u32,
+		/// How many votes a single account can cast on the proposals of the track per round,
+		/// or the most voice credits it can spend on them if votes are paid for with credits.
+		pub max_votes_per_account: u32,
+		/// The fewest votes, for and against, a proposal needs before it can win.
+		pub minimum_turnout: u32,
+		/// The fewest accounts that have to vote on a proposal before it can win.
+		pub minimum_voters: u32,
+		/// Decides whether a proposal that reached the quorum wins, `Config::Threshold`
+		/// if not set.
+		pub threshold: Option<ThresholdRule>,
+		/// Whether every approved proposal wins or only the highest ranked ones.
+		pub selection: SelectionMode,
+	}
+
+	/// The tracks registered by the admin. The default track is only stored here when it
+	/// has been registered with parameters other than those set in `Config`.
+	#[pallet::storage]
+	pub type Tracks<T: Config> = CountedStorageMap<_, Twox64Concat, TrackId, TrackInfo<T>>;
+
+	/// Determines if a track is in the proposal or voting period.
 	/// We are using the unit expression to achieve a slightly
 	/// more optimized way of storing a boolean.
-	/// When 'false', the pallet is considered to be in the
+	/// When 'false', the track is considered to be in the
 	/// voting period and will allow votes to be cast on existing
 	/// proposals. When 'true', the opposite is true and proposals
 	/// can be made up until the time is up or the maximum threshold
 	/// of proposals has been reached.
 	#[pallet::storage]
-	pub type ProposalPeriod<T> = StorageValue<_, ()>;
+	pub type ProposalPeriod<T> = StorageMap<_, Twox64Concat, TrackId, ()>;
+
+	/// Set while a track waits for the next proposal period after a voting period.
+	/// `ProposalPeriod` is set as well, so no votes are accepted.
+	#[pallet::storage]
+	pub type CooldownPeriod<T> = StorageMap<_, Twox64Concat, TrackId, ()>;
 
-	/// There's a possibility that clearing the proposals will not work.
-	/// In which case a pointer to the continuation will be stored here.
-	/// The use of unbounded here should be okay here as `MaxProposals`
-	/// bounds the size of the proposal storage.
+	/// The block the cycle was paused in by the admin. While set, the phases of no track end
+	/// and neither proposals nor votes are accepted.
 	#[pallet::storage]
-	#[pallet::unbounded]
-	pub type LeftoverProposalCursor<T: Config> = StorageValue<_, Vec<u8>>;
+	pub type Paused<T: Config> = StorageValue<_, BlockNumberFor<T>>;
 
+	/// The block the current phase of a track started in.
+	#[pallet::storage]
+	pub type PhaseStart<T: Config> =
+		StorageMap<_, Twox64Concat, TrackId, BlockNumberFor<T>, ValueQuery>;
+
+	/// The creator of every live proposal, across all tracks.
 	#[pallet::storage]
 	pub(super) type CountedProposals<T: Config> =
 		CountedStorageMap<_, Blake2_128Concat, [u8; 32], T::AccountId>;
 
+	/// The track every live proposal runs on.
+	#[pallet::storage]
+	pub type TrackOf<T> = StorageMap<_, Blake2_128Concat, [u8; 32], TrackId, ValueQuery>;
+
+	/// What a proposal is about, so voters can learn on chain what they are voting on.
+	#[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ProposalMetadata<T: Config> {
+		pub title: BoundedVec<u8, T::MaxTitleLength>,
+		pub description_uri: BoundedVec<u8, T::MaxDescriptionUriLength>,
+		/// A SCALE encoded runtime call, the proposal hash is its blake2_256 hash.
+		pub call: Option<BoundedVec<u8, T::MaxCallLength>>,
+		/// The enacted proposal this proposal repeals, if it is a repeal proposal.
+		pub repeals: Option<[u8; 32]>,
+		/// The account that created the proposal and is refunded the deposits.
+		pub depositor: T::AccountId,
+		pub deposit: BalanceOf<T>,
+		/// The `ProposalDeposit` at the time the proposal was created.
+		pub proposal_deposit: BalanceOf<T>,
+	}
+
+	/// The metadata of every live proposal.
+	/// Removed, and the deposit returned, when the proposal is withdrawn or its voting
+	/// period ends.
+	#[pallet::storage]
+	pub type MetadataOf<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 32], ProposalMetadata<T>>;
+
+	/// A winning proposal waiting for its enactment.
+	#[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PendingEnactment<T: Config> {
+		/// The block the proposal is enacted in.
+		pub when: BlockNumberFor<T>,
+		/// The call carried by the proposal, kept after its metadata is released.
+		pub call: Option<BoundedVec<u8, T::MaxCallLength>>,
+		/// The enacted proposal this proposal repeals, if it is a repeal proposal.
+		pub repeals: Option<[u8; 32]>,
+	}
+
+	/// The beneficiary of a funding proposal and what has been contributed to it in the
+	/// current voting round.
+	#[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Funding<T: Config> {
+		/// The account contributions and the proposal's match are transferred to.
+		pub beneficiary: T::AccountId,
+		/// The total contributed by every contributor.
+		pub contributions: BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxContributors>,
+	}
+
+	/// The funding of every live funding proposal. Removed when the proposal is withdrawn
+	/// or the matching pool is distributed at the end of its voting period.
+	#[pallet::storage]
+	pub type FundingOf<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 32], Funding<T>>;
+
+	/// Winning proposals that are scheduled but not yet enacted.
+	#[pallet::storage]
+	pub type PendingEnactments<T: Config> =
+		StorageMap<_, Blake2_128Concat, [u8; 32], PendingEnactment<T>>;
+
 	#[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct Voter<T: Config> {
-		total_votes: u32,
+		/// The voting round these votes were cast in.
+		round: u32,
+		/// The part of the vote budget spent on these votes, see `VoteCostModel`.
+		spent: u32,
 		amount_reserved: BalanceOf<T>,
-		votes_per_proposal: BoundedVec<u32, T::MaxVotesPerAccount>,
+		/// Votes cast by this account, keyed by the proposal hash.
+		votes_per_proposal: BoundedVec<([u8; 32], VoteRecord), T::MaxProposals>,
+	}
+
+	impl<T: Config> Voter<T> {
+		/// The votes this voter has cast on `proposal`.
+		fn votes_on(&self, proposal: &[u8; 32]) -> VoteRecord {
+			self.votes_per_proposal
+				.iter()
+				.find(|(p, _)| p == proposal)
+				.map(|(_, votes)| *votes)
+				.unwrap_or_default()
+		}
+
+		/// The proposals this voter holds votes on, if they were cast in `round`.
+		pub(crate) fn proposals_in(&self, round: u32) -> Vec<[u8; 32]> {
+			match self.round == round {
+				true => self.votes_per_proposal.iter().map(|(p, _)| *p).collect(),
+				false => Vec::new(),
+			}
+		}
+
+		/// Set the votes this voter has cast on `proposal`, retracting all votes drops the entry.
+		fn set_votes_on(&mut self, proposal: [u8; 32], votes: VoteRecord) -> DispatchResult {
+			if votes.total() == 0 {
+				self.votes_per_proposal.retain(|(p, _)| *p != proposal);
+				return Ok(())
+			}
+			match self.votes_per_proposal.iter_mut().find(|(p, _)| *p == proposal) {
+				Some((_, v)) => *v = votes,
+				None => self
+					.votes_per_proposal
+					.try_push((proposal, votes))
+					.map_err(|_| Error::<T>::TooManyProposals)?,
+			}
+			Ok(())
+		}
+	}
+
+	/// The votes a single voter has cast for and against a single proposal.
+	#[derive(
+		Encode, Decode, Debug, TypeInfo, MaxEncodedLen, Eq, PartialEq, Clone, Copy, Default,
+	)]
+	pub struct VoteRecord {
+		pub aye: u32,
+		pub nay: u32,
+	}
+
+	impl VoteRecord {
+		/// All votes in either direction, which is what the voter pays for.
+		pub fn total(&self) -> u32 {
+			self.aye.saturating_add(self.nay)
+		}
+
+		/// Add votes to this record, `None` on overflow. Votes for and against are counted
+		/// apart, a voter that changes its mind lowers the other side with `adjust_vote`.
+		pub fn add(&self, votes_for: u32, votes_against: u32) -> Option<VoteRecord> {
+			Some(VoteRecord {
+				aye: self.aye.checked_add(votes_for)?,
+				nay: self.nay.checked_add(votes_against)?,
+			})
+		}
+	}
+
+	/// The phase the pallet is in, see the crate level documentation.
+	#[derive(Encode, Decode, Debug, TypeInfo, Eq, PartialEq, Clone, Copy)]
+	pub enum Phase {
+		Proposal,
+		Voting,
+		/// The gap between a voting period and the next proposal period.
+		Cooldown,
 	}
+
 	#[derive(Encode, Decode, Debug, TypeInfo, MaxEncodedLen, Eq, PartialEq, Clone)]
 	pub struct VotingProposal {
-		proposal: [u8; 32],
-		votes_for: u32,
-		votes_against: u32,
+		pub proposal: [u8; 32],
+		pub votes_for: u32,
+		pub votes_against: u32,
 	}
 
-	/// This is where the proposals that get voted in get stored
-	/// TODO: repeals process.
+	/// This is where the proposals that get voted in get stored, once their enactment
+	/// period is over. Winning repeal proposals remove their target from here.
 	/// TODO: storing something more relevant than the unit might be interesting.
 	#[pallet::storage]
 	#[pallet::getter(fn get_all_enacted_proposals)]
 	pub type EnactedProposals<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 32], ()>;
 
-	/// Handles the current voting period's voters.
+	/// The index of the current voting round of a track, bumped every time its voting period
+	/// ends. Voters from earlier rounds are waiting to be refunded.
+	#[pallet::storage]
+	pub type VotingRound<T> = StorageMap<_, Twox64Concat, TrackId, u32, ValueQuery>;
+
+	/// How many accounts hold votes on each proposal of the current voting round.
+	#[pallet::storage]
+	pub type ProposalVoters<T> = StorageMap<_, Blake2_128Concat, [u8; 32], u32, ValueQuery>;
+
+	/// The accounts holding votes on each proposal of the current voting round, the ones
+	/// counted in `ProposalVoters`.
+	#[pallet::storage]
+	pub type VotersOf<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, [u8; 32], Blake2_128Concat, T::AccountId, ()>;
+
+	/// How many accounts have voted in the current voting round of a track.
+	#[pallet::storage]
+	pub type SessionVoters<T> = StorageMap<_, Twox64Concat, TrackId, u32, ValueQuery>;
+
+	/// Handles the voters of the current voting period of every track, as well as voters
+	/// from earlier rounds that have not been refunded yet.
 	#[pallet::storage]
 	#[pallet::getter(fn get_all_voters)]
-	pub type Voters<T: Config> = CountedStorageMap<_, Blake2_128Concat, T::AccountId, Voter<T>>;
+	pub type Voters<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, TrackId, Blake2_128Concat, T::AccountId, Voter<T>>;
 
-	/// A vector of the proposed proposals that can be quickly fetched and computed.
+	/// A vector of the proposed proposals of every track that can be quickly fetched and
+	/// computed.
 	#[pallet::storage]
-	pub type Proposals<T: Config> = StorageValue<_, BoundedVec<VotingProposal, T::MaxProposals>>;
+	pub type Proposals<T: Config> =
+		StorageMap<_, Twox64Concat, TrackId, BoundedVec<VotingProposal, T::MaxProposals>>;
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/v3/runtime/events-and-errors
@@ -156,10 +568,26 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		ProposalCreated { proposal: [u8; 32] },
 		ProposalWithdrawn { proposal: [u8; 32] },
-		VotingPeriodEnded { block: BlockNumberFor<T> },
-		ProposalPeriodEnded { block: BlockNumberFor<T> },
+		VotingPeriodEnded { track: TrackId, block: BlockNumberFor<T> },
+		ProposalPeriodEnded { track: TrackId, block: BlockNumberFor<T> },
+		CooldownPeriodEnded { track: TrackId, block: BlockNumberFor<T> },
 		VoteRegistered { proposal: [u8; 32], who: T::AccountId },
+		VoteAdjusted { proposal: [u8; 32], who: T::AccountId },
+		RefundClaimed { who: T::AccountId, amount: BalanceOf<T> },
+		ProposalTallied { proposal: [u8; 32], tally: Tally, outcome: Outcome },
 		WinningProposals { winners: BoundedVec<VotingProposal, T::MaxProposals> },
+		ProposalScheduled { proposal: [u8; 32], when: BlockNumberFor<T> },
+		ProposalNotScheduled { proposal: [u8; 32] },
+		ProposalEnacted { proposal: [u8; 32], result: DispatchResult },
+		ProposalRepealed { proposal: [u8; 32], target: [u8; 32] },
+		Contributed { proposal: [u8; 32], who: T::AccountId, amount: BalanceOf<T> },
+		MatchingDistributed { proposal: [u8; 32], beneficiary: T::AccountId, amount: BalanceOf<T> },
+		ProposalCancelled { proposal: [u8; 32] },
+		DepositSlashed { proposal: [u8; 32], who: T::AccountId, amount: BalanceOf<T> },
+		CyclePaused { block: BlockNumberFor<T> },
+		CycleResumed { block: BlockNumberFor<T> },
+		TrackRegistered { track: TrackId },
+		TrackRemoved { track: TrackId },
 	}
 
 	// Errors inform users that something went wrong.
@@ -183,10 +611,48 @@ pub mod pallet {
 		ProposalDoesNotExist,
 		// Max proposal threshold reached for this period
 		TooManyProposals,
-		// The proposal index is out of bounds
-		ProposalIndexOutOfBounds,
-		// This account has used up all their votes for this voting period
+		// The proposal hash is not the hash of the call it carries
+		PreimageMismatch,
+		// The call carried by a proposal can not be decoded
+		UndecodableCall,
+		// The call carried by a proposal weighs more than `MaxCallWeight`
+		CallTooHeavy,
+		// The call carried by a proposal is not let through by `ProposalFilter`
+		CallFiltered,
+		// The proposal is not waiting for its enactment
+		ProposalNotPending,
+		// The proposal to be repealed has not been enacted
+		NotEnacted,
+		// The proposal does not take contributions
+		NotAFundingProposal,
+		// Contributions have to be larger than zero
+		ZeroContribution,
+		// Max contributor threshold reached for this proposal
+		TooManyContributors,
+		// The cycle has been paused by the admin
+		CyclePaused,
+		// The cycle is not paused
+		NotPaused,
+		// There is no track with this id
+		UnknownTrack,
+		// The parameters of the track can not run a cycle
+		InvalidTrack,
+		// Max track threshold reached
+		TooManyTracks,
+		// The track still has proposals or voters
+		TrackInUse,
+		// This account has used up its vote budget for this voting period
 		AllVotesCastForAccount,
+		// Max voter threshold reached for this voting period
+		TooManyVoters,
+		// The first vote of an account in a voting period casts no votes
+		EmptyVote,
+		// There is no reserve from an earlier voting round to refund
+		NoRefundDue,
+		// This account has no votes on the proposal in the current voting period
+		NoVotesToAdjust,
+		// Votes can only be lowered when adjusting a vote
+		CanOnlyLowerVotes,
 		// User input at fault
 		MathError,
 		// The Sky is falling, all bets are off
@@ -196,78 +662,51 @@ pub mod pallet {
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
-			let period_length = T::PeriodLength::get();
-			match ProposalPeriod::<T>::exists() {
-				true => {
-					if now % period_length.into() == 0u32.into() {
-						// The proposal period has ended, this block and forward will not
-						// validate any new proposals or withdrawal requests.
-						ProposalPeriod::<T>::kill();
-						Self::deposit_event(Event::ProposalPeriodEnded { block: now })
-					}
-				},
-				false => {
-					if now % period_length.into() == 0u32.into() {
-						// The voting period has ended, this block and forward will not
-						//println!("3we here");
-						// validate any votes cast.
-						ProposalPeriod::<T>::put(());
-						Self::deposit_event(Event::VotingPeriodEnded { block: now });
-						// Calculate winning proposals
-						let maybe_proposals = Proposals::<T>::get();
-						match maybe_proposals {
-							Some(proposals) => {
-								let winners: BoundedVec<VotingProposal, T::MaxProposals> =
-									BoundedVec::truncate_from(
-										proposals
-											.into_inner()
-											.into_iter()
-											.filter(|p| p.votes_for > p.votes_against)
-											.map(|p| {
-												EnactedProposals::<T>::insert(p.proposal, ());
-												return p;
-											})
-											.collect(),
-									);
-								// Store the winning proposals
-
-								Self::deposit_event(Event::WinningProposals { winners })
-							},
-							None => (), //No proposals, no winners.
-						};
-
-						// This is why we bound the number of voters.
-
-						let voter_iter = Voters::<T>::iter_keys();
-						voter_iter.for_each(|v_key| {
-							// remove from storage
-							if let Some(voter) = Voters::<T>::take(&v_key) {
-								// refund the reserve
-								T::Currency::unreserve(&v_key, voter.amount_reserved);
-							}
-						});
+			// The cycle stands still until the admin resumes it.
+			if Paused::<T>::exists() {
+				return T::WeightInfo::on_initialize_idle()
+			}
+			// Every track runs its own cycle.
+			Self::track_ids().into_iter().fold(0, |weight: Weight, track| {
+				weight.saturating_add(Self::progress_track(track, now))
+			})
+		}
 
-						Proposals::<T>::kill();
-						// The only time this is called from here is the beginning of the voting
-						// period. Therefore we can safely assume that None can always be passed
-						// as long as `MaxProposals` is > 1
-						let proposal_result =
-							CountedProposals::<T>::clear(T::MaxProposals::get(), None);
-						LeftoverProposalCursor::<T>::set(proposal_result.maybe_cursor);
-					} else {
-						// Continue to clean up the proposals and voters for as long as there
-						// exists a cursor.
-						let leftover_proposals = LeftoverProposalCursor::<T>::get();
-						if let Some(cursor) = leftover_proposals {
-							let result =
-								CountedProposals::<T>::clear(T::MaxProposals::get(), Some(&cursor));
-							LeftoverProposalCursor::<T>::set(result.maybe_cursor);
-						}
+		fn integrity_test() {
+			assert!(T::MaxRefundsPerBlock::get() > 0, "MaxRefundsPerBlock must be non-zero");
+			assert!(T::ProposalPeriodLength::get() > 0, "ProposalPeriodLength must be non-zero");
+			assert!(T::VotingPeriodLength::get() > 0, "VotingPeriodLength must be non-zero");
+			assert!(
+				T::MinimumVoters::get() <= T::MaxVotersPerSession::get(),
+				"MinimumVoters can not exceed the voters a round has room for"
+			);
+			assert!(
+				T::Selection::get() != SelectionMode::TopK(0),
+				"TopK selection must pick at least one proposal"
+			);
+			// The scheduler can not schedule for the block that is being initialized.
+			assert!(!T::EnactmentPeriod::get().is_zero(), "EnactmentPeriod must be non-zero");
+			assert!(
+				T::MaxCallWeight::get().saturating_mul(T::MaxProposals::get().into()) <
+					T::BlockWeights::get().max_block,
+				"The calls of all proposals must fit in a block"
+			);
+			// See the contract of `VoteCostFunction`.
+			assert_eq!(T::VoteCostFunction::cost(0), Some(0), "No votes must cost nothing");
+			assert!(
+				(0..T::MaxVotesPerAccount::get()).all(|votes| {
+					match (T::VoteCostFunction::cost(votes), T::VoteCostFunction::cost(votes + 1)) {
+						(Some(cost), Some(next)) => cost <= next,
+						(cost, next) => cost.is_none() <= next.is_none(),
 					}
-				},
-			}
-			// TODO: Figure out weight
-			0
+				}),
+				"More votes must never cost less"
+			);
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
 		}
 	}
 
@@ -276,59 +715,39 @@ pub mod pallet {
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Create a proposal that can be voted on.
-		/// TODO: The weight should be adjusted in order to control for spam.
-		#[pallet::weight(1_000)]
-		pub fn create_proposal(origin: OriginFor<T>, proposal: [u8; 32]) -> DispatchResult {
+		/// Create a proposal that can be voted on in `track`.
+		/// The proposal carries a title, a URI describing it and optionally an encoded call,
+		/// in which case `proposal` must be the blake2_256 hash of the call. A deposit
+		/// proportional to the size of this metadata is reserved until the proposal is
+		/// withdrawn or its voting period ends, along with `ProposalDeposit`, which is
+		/// partially slashed unless the proposal reaches the quorum.
+		#[pallet::weight(T::WeightInfo::create_proposal(T::MaxProposals::get()))]
+		pub fn create_proposal(
+			origin: OriginFor<T>,
+			track: TrackId,
+			proposal: [u8; 32],
+			title: BoundedVec<u8, T::MaxTitleLength>,
+			description_uri: BoundedVec<u8, T::MaxDescriptionUriLength>,
+			call: Option<BoundedVec<u8, T::MaxCallLength>>,
+		) -> DispatchResult {
 			// Is the transaction signed
 			let creator = ensure_signed(origin)?;
 
-			// Is the runtime in a proposal period
-			ensure!(ProposalPeriod::<T>::exists(), Error::<T>::NotInProposalPeriod);
-
-			// Is the creator identified
-			ensure!(
-				<<T as Config>::IdentityProvider as IdentityInterface<T::AccountId>>::is_identified(
-					&creator
-				),
-				Error::<T>::NotIdentified
-			);
-
-			// Does the proposal exist already
-			ensure!(
-				!CountedProposals::<T>::contains_key(proposal),
-				Error::<T>::ProposalAlreadyExists
-			);
-
-			// Does the runtime allow for more proposals to be added
-			ensure!(
-				CountedProposals::<T>::count() < T::MaxProposals::get(),
-				Error::<T>::TooManyProposals
-			);
-
-			// Cool, continue with storage entry.
-			CountedProposals::<T>::insert(proposal, creator);
-			let res = Proposals::<T>::try_append(VotingProposal {
+			Self::do_create_proposal(
+				creator,
+				track,
 				proposal,
-				votes_for: 0,
-				votes_against: 0,
-			});
-
-			// TODO: catch and roll back here for extra defensive coding
-			match res {
-				Ok(_) => (),
-				Error => (),
-			};
-
-			Self::deposit_event(Event::ProposalCreated { proposal });
-			Ok(())
+				title,
+				description_uri,
+				call,
+				None,
+				None,
+			)
 		}
 
 		/// Withdraw a proposal if still within the same voting period.
-		/// TODO: This should probably cost more to stop people from wasting others' time.
-		/// TODO: Adjust in relation with create_proposal weights.
-		/// TODO: Also read up on benchmarking.
-		#[pallet::weight(10_000)]
+		/// The `DepositSlash` share of the proposal deposit is slashed.
+		#[pallet::weight(T::WeightInfo::withdraw_proposal(T::MaxProposals::get()))]
 		pub fn withdraw_proposal(origin: OriginFor<T>, proposal: [u8; 32]) -> DispatchResult {
 			// Is the transaction signed
 			let sender = ensure_signed(origin)?;
@@ -337,36 +756,49 @@ pub mod pallet {
 			// already been made in create_proposal and the account might have deregistered
 			// their identity while still having a proposal active.
 
-			// Is the runtime in a proposal period
-			ensure!(ProposalPeriod::<T>::exists(), Error::<T>::NotInProposalPeriod);
-
 			// Did this account create this proposal? Does it exist?
 			match CountedProposals::<T>::get(proposal) {
 				Some(creator) => ensure!(sender == creator, Error::<T>::NotYourProposal),
 				None => fail!(Error::<T>::ProposalDoesNotExist),
 			}
-			CountedProposals::<T>::remove(proposal);
+
+			// Is the track of the proposal in a proposal period
+			ensure!(!Paused::<T>::exists(), Error::<T>::CyclePaused);
+			ensure!(
+				Self::phase(TrackOf::<T>::get(proposal)) == Phase::Proposal,
+				Error::<T>::NotInProposalPeriod
+			);
+			Self::remove_proposal(&proposal, true);
 			Self::deposit_event(Event::ProposalWithdrawn { proposal });
 			Ok(())
 		}
 
 		/// Cast a vote on a proposal in the current period.
-		/// When a vote is cast, an amount of tokens equal to the number of votes multiplied by itself
-		/// will be reserved. Currency reserved to cast votes will not be released until the end of the
-		/// voting period.
-		/// TODO: Weights.
-		#[pallet::weight(1_000)]
+		/// When a vote is cast, `VoteCostUnit` times the `VoteCostFunction` cost of the votes
+		/// held on the proposal is reserved, the number of votes multiplied by itself for
+		/// `Quadratic`. The reserve is released when votes are lowered with `adjust_vote`,
+		/// when the proposal is cancelled and otherwise once the voting period has ended,
+		/// lazily or through `claim_refund`. With a `CostModel` that does not reserve funds
+		/// the votes are paid for out of the voter's budget alone.
+		/// The proposal is addressed by its hash so a vote can never land on a different
+		/// proposal if `Proposals` changes between signing and inclusion.
+		#[pallet::weight(T::WeightInfo::cast_vote(T::MaxProposals::get()))]
 		pub fn cast_vote(
 			origin: OriginFor<T>,
-			proposal_index: u32,
+			proposal: [u8; 32],
 			votes_for: u32,
 			votes_against: u32,
 		) -> DispatchResult {
 			// Is the transaction signed
 			let sender = ensure_signed(origin)?;
 
-			// Is the voting period active
-			ensure!(!ProposalPeriod::<T>::exists(), Error::<T>::NotInVotingPeriod);
+			// Does the proposal exist
+			ensure!(TrackOf::<T>::contains_key(proposal), Error::<T>::ProposalDoesNotExist);
+
+			// Is the voting period of the proposal's track active
+			let track = TrackOf::<T>::get(proposal);
+			ensure!(!Paused::<T>::exists(), Error::<T>::CyclePaused);
+			ensure!(!ProposalPeriod::<T>::contains_key(track), Error::<T>::NotInVotingPeriod);
 
 			// Is the voter identified
 			ensure!(
@@ -376,91 +808,1322 @@ pub mod pallet {
 				Error::<T>::NotIdentified
 			);
 
-			// Is the proposal index within bounds
-			let is_index_inside_bounds =
-				proposal_index.ge(&0) && proposal_index.lt(&T::MaxProposals::get());
-			ensure!(is_index_inside_bounds, Error::<T>::ProposalIndexOutOfBounds);
+			// Fetch the proposals and find the one being voted on
+			let mut proposals;
+			match Proposals::<T>::get(track) {
+				Some(ps) => proposals = ps,
+				None => fail!(Error::<T>::ProposalDoesNotExist),
+			}
+			let proposal_index;
+			match proposals.iter().position(|p| p.proposal == proposal) {
+				Some(index) => proposal_index = index,
+				None => fail!(Error::<T>::ProposalDoesNotExist),
+			}
+			let info;
+			match Self::track_info(track) {
+				Some(i) => info = i,
+				None => fail!(Error::<T>::UnknownTrack),
+			}
 
+			let round = VotingRound::<T>::get(track);
 			let mut voter;
-			match Voters::<T>::get(&sender) {
-				Some(v) => voter = v,
-				None => {
-					// This should be okay due to the bounds
-					let mut bv = Vec::new();
-					for _ in 0..T::MaxProposals::get() {
-						bv.push(0u32);
-					}
+			let mut is_new_voter = false;
+			let mut stale_reserve = None;
+			match Voters::<T>::get(track, &sender) {
+				Some(v) if v.round == round => voter = v,
+				maybe_stale => {
+					// Is there room for another voter in this voting period
+					ensure!(
+						SessionVoters::<T>::get(track) < T::MaxVotersPerSession::get(),
+						Error::<T>::TooManyVoters
+					);
+					// A reserve left over from an earlier round is refunded along with this vote.
+					is_new_voter = true;
+					stale_reserve = maybe_stale.map(|v| v.amount_reserved);
 					voter = Voter::<T> {
-						total_votes: 0u32,
+						round,
+						spent: 0u32,
 						amount_reserved: 0u32.into(),
-						votes_per_proposal: BoundedVec::truncate_from(bv),
+						votes_per_proposal: BoundedVec::default(),
 					}
 				},
 			};
 
-			// Tally up account's votes
-			let sum_votes_in;
-			match votes_for.checked_add(votes_against) {
-				Some(sum) => sum_votes_in = sum,
+			// The votes this account already has for this proposal
+			let old_votes = voter.votes_on(&proposal);
+
+			// Add the new votes to the account's position on this proposal
+			let new_votes;
+			match old_votes.add(votes_for, votes_against) {
+				Some(votes) => new_votes = votes,
 				None => fail!(Error::<T>::MathError),
-			};
+			}
+			// A new voter takes up one of the `MaxVotersPerSession` slots, so it has to cast votes
+			ensure!(!is_new_voter || new_votes.total() > 0, Error::<T>::EmptyVote);
 
-			let new_total_votes;
-			match voter.total_votes.checked_add(sum_votes_in) {
-				Some(sum) => new_total_votes = sum,
+			// Tally up the part of the account's budget its votes spend
+			let new_spent;
+			match Self::vote_spend(old_votes.total())
+				.zip(Self::vote_spend(new_votes.total()))
+				.and_then(|(old, new)| voter.spent.checked_sub(old)?.checked_add(new))
+			{
+				Some(spent) => new_spent = spent,
 				None => fail!(Error::<T>::MathError),
 			};
 
-			// Is the account's total account tally  greater than MaxVotesPerAccount
+			// Does the account's budget on this track cover its votes
 			ensure!(
-				new_total_votes <= T::MaxVotesPerAccount::get(),
+				new_spent <= T::CostModel::budget(&sender, info.max_votes_per_account),
 				Error::<T>::AllVotesCastForAccount
 			);
 
+			// Since there might already be old reserves, only the difference between
+			// the old and the new reserve is reserved or released.
+			let old_reserve = Self::vote_reserve(old_votes.total())?;
+			let new_full_reserve = Self::vote_reserve(new_votes.total())?;
+			if let Some(amount) = stale_reserve {
+				T::Currency::unreserve(&sender, amount);
+			}
+			if new_full_reserve >= old_reserve {
+				let new_reserve = new_full_reserve - old_reserve;
+				T::Currency::reserve(&sender, new_reserve)?;
+				match voter.amount_reserved.checked_add(&new_reserve) {
+					Some(sum) => voter.amount_reserved = sum,
+					None => fail!(Error::<T>::MathError),
+				}
+			} else {
+				let refund = old_reserve - new_full_reserve;
+				match voter.amount_reserved.checked_sub(&refund) {
+					Some(sub) => voter.amount_reserved = sub,
+					None => fail!(Error::<T>::MathError),
+				}
+				T::Currency::unreserve(&sender, refund);
+			}
+
 			// Update the vote state for this account.
-			let old_votes = voter.votes_per_proposal[proposal_index as usize];
-			voter.votes_per_proposal[proposal_index as usize] += sum_votes_in;
+			voter.set_votes_on(proposal, new_votes)?;
+			voter.spent = new_spent;
 
-			// The number of votes this account has for this index
-			// NOTE: We count votes for and votes against equally, so
-			// votes_for: 2, votes_against: 2 in 2 or more calls will count
-			// as 4 votes and not 0 votes. Consider this a price on indecision :-)
-			let new_votes;
-			match old_votes.checked_add(sum_votes_in) {
-				Some(sum) => new_votes = sum,
-				None => fail!(Error::<T>::MathError),
+			// Replace this account's old position in the proposal's tally with the new one
+			match (
+				proposals[proposal_index]
+					.votes_for
+					.checked_sub(old_votes.aye)
+					.and_then(|rest| rest.checked_add(new_votes.aye)),
+				proposals[proposal_index]
+					.votes_against
+					.checked_sub(old_votes.nay)
+					.and_then(|rest| rest.checked_add(new_votes.nay)),
+			) {
+				(Some(votes_for), Some(votes_against)) => {
+					proposals[proposal_index].votes_for = votes_for;
+					proposals[proposal_index].votes_against = votes_against;
+				},
+				_ => fail!(Error::<T>::MathError),
 			}
 
-			// Since there might already be old reserves, we subtract the
-			// new total from the old total to get the new reserve to add.
-			let old_reserve = old_votes.pow(2);
-			let new_full_reserve: u32 = new_votes.pow(2);
-			let new_reserve: u32;
-			match new_full_reserve.checked_sub(old_reserve) {
-				Some(sub) => new_reserve = sub,
-				None => fail!(Error::<T>::MathError),
+			if is_new_voter {
+				SessionVoters::<T>::mutate(track, |count| *count += 1);
+			}
+			// Votes are only added here, the first votes on the proposal make the account
+			// one of its voters.
+			if old_votes.total() == 0 && new_votes.total() > 0 {
+				ProposalVoters::<T>::mutate(proposal, |count| *count += 1);
+				VotersOf::<T>::insert(proposal, &sender, ());
+			}
+			Proposals::<T>::insert(track, proposals);
+			Voters::<T>::insert(track, &sender, voter);
+
+			Self::deposit_event(Event::VoteRegistered { proposal, who: sender });
+
+			Ok(())
+		}
+
+		/// Lower the votes cast on a proposal in the current voting period to `votes_for`
+		/// and `votes_against`. The difference in reserve between the old and the new
+		/// number of votes is released straight away, adjusting to zero retracts the vote.
+		#[pallet::weight(T::WeightInfo::adjust_vote(T::MaxProposals::get()))]
+		pub fn adjust_vote(
+			origin: OriginFor<T>,
+			proposal: [u8; 32],
+			votes_for: u32,
+			votes_against: u32,
+		) -> DispatchResult {
+			// Is the transaction signed
+			let sender = ensure_signed(origin)?;
+
+			// There is no need to check for identity as the votes being lowered were
+			// cast while the account was identified.
+
+			// Does the proposal exist
+			ensure!(TrackOf::<T>::contains_key(proposal), Error::<T>::ProposalDoesNotExist);
+
+			// Is the voting period of the proposal's track active
+			let track = TrackOf::<T>::get(proposal);
+			ensure!(!Paused::<T>::exists(), Error::<T>::CyclePaused);
+			ensure!(!ProposalPeriod::<T>::contains_key(track), Error::<T>::NotInVotingPeriod);
+
+			// Has this account voted in the current round
+			let mut voter;
+			match Voters::<T>::get(track, &sender) {
+				Some(v) if v.round == VotingRound::<T>::get(track) => voter = v,
+				_ => fail!(Error::<T>::NoVotesToAdjust),
 			}
-			T::Currency::reserve(&sender, new_reserve.into())?;
-			// Update voter total balance
-			voter.amount_reserved = new_full_reserve.into();
-			voter.total_votes = new_total_votes;
 
-			// Finally we fetch the proposals
+			// Votes can only be lowered, never raised or moved to the other side
+			let old_votes = voter.votes_on(&proposal);
+			ensure!(old_votes.total() > 0, Error::<T>::NoVotesToAdjust);
+			ensure!(
+				votes_for <= old_votes.aye && votes_against <= old_votes.nay,
+				Error::<T>::CanOnlyLowerVotes
+			);
+			let new_votes = VoteRecord { aye: votes_for, nay: votes_against };
+			let removed_aye = old_votes.aye - new_votes.aye;
+			let removed_nay = old_votes.nay - new_votes.nay;
+
+			// Fetch the proposals and find the one being adjusted
 			let mut proposals;
-			match Proposals::<T>::get() {
+			match Proposals::<T>::get(track) {
 				Some(ps) => proposals = ps,
-				None => fail!(Error::<T>::SkyIsFalling),
+				None => fail!(Error::<T>::ProposalDoesNotExist),
+			}
+			let proposal_index;
+			match proposals.iter().position(|p| p.proposal == proposal) {
+				Some(index) => proposal_index = index,
+				None => fail!(Error::<T>::ProposalDoesNotExist),
+			}
+			match (
+				proposals[proposal_index].votes_for.checked_sub(removed_aye),
+				proposals[proposal_index].votes_against.checked_sub(removed_nay),
+			) {
+				(Some(votes_for), Some(votes_against)) => {
+					proposals[proposal_index].votes_for = votes_for;
+					proposals[proposal_index].votes_against = votes_against;
+				},
+				_ => fail!(Error::<T>::MathError),
 			}
-			proposals[proposal_index as usize].votes_for += votes_for;
-			proposals[proposal_index as usize].votes_against += votes_against;
-			let proposal_hash = proposals[proposal_index as usize].proposal.clone();
 
-			Proposals::<T>::set(Some(proposals));
-			Voters::<T>::set(&sender, Some(voter));
+			// Release the difference between the old and the new reserve
+			let old_reserve = Self::vote_reserve(old_votes.total())?;
+			let new_full_reserve = Self::vote_reserve(new_votes.total())?;
+			let refund;
+			match old_reserve.checked_sub(&new_full_reserve) {
+				Some(sub) => refund = sub,
+				None => fail!(Error::<T>::MathError),
+			}
+			match voter.amount_reserved.checked_sub(&refund) {
+				Some(sub) => voter.amount_reserved = sub,
+				None => fail!(Error::<T>::MathError),
+			}
+			match Self::vote_spend(old_votes.total())
+				.zip(Self::vote_spend(new_votes.total()))
+				.and_then(|(old, new)| old.checked_sub(new))
+			{
+				Some(freed) => voter.spent = voter.spent.saturating_sub(freed),
+				None => fail!(Error::<T>::MathError),
+			}
+			voter.set_votes_on(proposal, new_votes)?;
+			T::Currency::unreserve(&sender, refund);
+			if new_votes.total() == 0 {
+				ProposalVoters::<T>::mutate(proposal, |count| *count = count.saturating_sub(1));
+				VotersOf::<T>::remove(proposal, &sender);
+			}
 
-			Self::deposit_event(Event::VoteRegistered { proposal: proposal_hash, who: sender });
+			Proposals::<T>::insert(track, proposals);
+			Voters::<T>::insert(track, &sender, voter);
+
+			Self::deposit_event(Event::VoteAdjusted { proposal, who: sender });
+
+			Ok(())
+		}
+
+		/// Refund the reserve `who` has left over from an earlier voting round of `track`.
+		/// Anyone can claim on behalf of `who`, the reserve is always returned to `who`.
+		#[pallet::weight(T::WeightInfo::claim_refund())]
+		pub fn claim_refund(
+			origin: OriginFor<T>,
+			track: TrackId,
+			who: T::AccountId,
+		) -> DispatchResult {
+			// Is the transaction signed
+			ensure_signed(origin)?;
 
+			// Does `who` have votes from an earlier round
+			match Voters::<T>::get(track, &who) {
+				Some(voter) if voter.round < VotingRound::<T>::get(track) => {
+					Voters::<T>::remove(track, &who);
+					T::Currency::unreserve(&who, voter.amount_reserved);
+					Self::deposit_event(Event::RefundClaimed {
+						who,
+						amount: voter.amount_reserved,
+					});
+				},
+				_ => fail!(Error::<T>::NoRefundDue),
+			}
 			Ok(())
 		}
+
+		/// Enact a winning proposal: record it in `EnactedProposals` and dispatch the call it
+		/// carries, if any, with `EnactmentOrigin`. Scheduled when the proposal wins, to be
+		/// dispatched as root `EnactmentPeriod` blocks after its voting period ended.
+		#[pallet::weight(T::WeightInfo::enact().saturating_add(T::MaxCallWeight::get()))]
+		pub fn enact(origin: OriginFor<T>, proposal: [u8; 32]) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			ensure!(PendingEnactments::<T>::contains_key(proposal), Error::<T>::ProposalNotPending);
+			let call_weight = Self::do_enact(&proposal);
+			Ok(Some(T::WeightInfo::enact().saturating_add(call_weight)).into())
+		}
+
+		/// Create a proposal in `track` to repeal the enacted proposal `target`. When it wins,
+		/// `target` is removed from `EnactedProposals` at the end of its enactment period.
+		/// The proposal is addressed by `Pallet::repeal_hash(target)` and otherwise behaves
+		/// like one created by `create_proposal` without a call.
+		#[pallet::weight(T::WeightInfo::propose_repeal(T::MaxProposals::get()))]
+		pub fn propose_repeal(
+			origin: OriginFor<T>,
+			track: TrackId,
+			target: [u8; 32],
+			title: BoundedVec<u8, T::MaxTitleLength>,
+			description_uri: BoundedVec<u8, T::MaxDescriptionUriLength>,
+		) -> DispatchResult {
+			// Is the transaction signed
+			let creator = ensure_signed(origin)?;
+
+			// Has the target been enacted
+			ensure!(EnactedProposals::<T>::contains_key(target), Error::<T>::NotEnacted);
+
+			let proposal = Self::repeal_hash(&target);
+			Self::do_create_proposal(
+				creator,
+				track,
+				proposal,
+				title,
+				description_uri,
+				None,
+				Some(target),
+				None,
+			)
+		}
+
+		/// Create a funding proposal in `track`, which can be contributed to during the voting
+		/// period. Contributions and the proposal's share of the matching pool are transferred
+		/// to `beneficiary`. Otherwise behaves like `create_proposal`.
+		#[pallet::weight(T::WeightInfo::create_funding_proposal(T::MaxProposals::get()))]
+		pub fn create_funding_proposal(
+			origin: OriginFor<T>,
+			track: TrackId,
+			proposal: [u8; 32],
+			title: BoundedVec<u8, T::MaxTitleLength>,
+			description_uri: BoundedVec<u8, T::MaxDescriptionUriLength>,
+			beneficiary: T::AccountId,
+		) -> DispatchResult {
+			// Is the transaction signed
+			let creator = ensure_signed(origin)?;
+
+			Self::do_create_proposal(
+				creator,
+				track,
+				proposal,
+				title,
+				description_uri,
+				None,
+				None,
+				Some(beneficiary),
+			)
+		}
+
+		/// Contribute `amount` to a funding proposal in the current voting period. The amount
+		/// is transferred to the proposal's beneficiary straight away and counts towards the
+		/// proposal's share of the matching pool.
+		#[pallet::weight(T::WeightInfo::contribute(T::MaxContributors::get()))]
+		pub fn contribute(
+			origin: OriginFor<T>,
+			proposal: [u8; 32],
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Is the transaction signed
+			let sender = ensure_signed(origin)?;
+
+			// Does the proposal exist
+			ensure!(TrackOf::<T>::contains_key(proposal), Error::<T>::ProposalDoesNotExist);
+
+			// Is the voting period of the proposal's track active
+			ensure!(!Paused::<T>::exists(), Error::<T>::CyclePaused);
+			ensure!(
+				!ProposalPeriod::<T>::contains_key(TrackOf::<T>::get(proposal)),
+				Error::<T>::NotInVotingPeriod
+			);
+
+			// Is the contributor identified
+			ensure!(
+				<<T as Config>::IdentityProvider as IdentityInterface<T::AccountId>>::is_identified(
+					&sender
+				),
+				Error::<T>::NotIdentified
+			);
+			ensure!(!amount.is_zero(), Error::<T>::ZeroContribution);
+
+			let mut funding;
+			match FundingOf::<T>::get(proposal) {
+				Some(f) => funding = f,
+				None => fail!(Error::<T>::NotAFundingProposal),
+			}
+
+			// Add to the account's earlier contributions, if any
+			match funding.contributions.iter_mut().find(|(who, _)| *who == sender) {
+				Some((_, total)) => match total.checked_add(&amount) {
+					Some(sum) => *total = sum,
+					None => fail!(Error::<T>::MathError),
+				},
+				None => funding
+					.contributions
+					.try_push((sender.clone(), amount))
+					.map_err(|_| Error::<T>::TooManyContributors)?,
+			}
+
+			T::Currency::transfer(
+				&sender,
+				&funding.beneficiary,
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			FundingOf::<T>::insert(proposal, funding);
+
+			Self::deposit_event(Event::Contributed { proposal, who: sender, amount });
+			Ok(())
+		}
+
+		/// End the current phase of `track` straight away and start the next one, as
+		/// `on_initialize` does once a phase has run its length.
+		#[pallet::weight(
+			T::WeightInfo::on_initialize_end_voting_period(
+				T::MaxProposals::get(),
+				T::MaxRefundsPerBlock::get(),
+			)
+			.saturating_add(T::WeightInfo::on_initialize_distribute_matching(T::MaxProposals::get()))
+		)]
+		pub fn force_end_phase(origin: OriginFor<T>, track: TrackId) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!Paused::<T>::exists(), Error::<T>::CyclePaused);
+
+			let info;
+			match Self::track_info(track) {
+				Some(i) => info = i,
+				None => fail!(Error::<T>::UnknownTrack),
+			}
+			let weight = Self::end_phase(track, &info, frame_system::Pallet::<T>::block_number());
+			Ok(Some(weight).into())
+		}
+
+		/// Cancel a live proposal, or veto a winning one before it is enacted. The votes
+		/// cast on a live proposal are refunded straight away and its metadata deposit is
+		/// returned, the `DepositSlash` share of its proposal deposit is slashed.
+		/// Contributions to a funding proposal have already been transferred and are not
+		/// refunded.
+		#[pallet::weight(T::WeightInfo::cancel_proposal(T::MaxVotersPerSession::get()))]
+		pub fn cancel_proposal(
+			origin: OriginFor<T>,
+			proposal: [u8; 32],
+		) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let voters;
+			if CountedProposals::<T>::contains_key(proposal) {
+				voters = Self::refund_votes_on(&proposal)?;
+				Self::remove_proposal(&proposal, true);
+			} else if PendingEnactments::<T>::take(proposal).is_some() {
+				voters = 0;
+				// Should the schedule be gone, `enact` still only acts on pending proposals.
+				let _ = T::Scheduler::cancel_named((ENACTMENT_ID, proposal).encode());
+			} else {
+				fail!(Error::<T>::ProposalDoesNotExist)
+			}
+
+			Self::deposit_event(Event::ProposalCancelled { proposal });
+			Ok(Some(T::WeightInfo::cancel_proposal(voters)).into())
+		}
+
+		/// Pause the cycle: the current phase does not end and neither proposals nor votes
+		/// are accepted until the cycle is resumed.
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(origin: OriginFor<T>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!Paused::<T>::exists(), Error::<T>::CyclePaused);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			Paused::<T>::put(now);
+			Self::deposit_event(Event::CyclePaused { block: now });
+			Ok(())
+		}
+
+		/// Resume a paused cycle. The current phase of every track continues for the blocks
+		/// it had left when the cycle was paused. A track registered while the cycle was
+		/// paused keeps the proposal period it started on registration.
+		#[pallet::weight(T::WeightInfo::resume(T::MaxTracks::get().saturating_add(1)))]
+		pub fn resume(origin: OriginFor<T>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let paused_at;
+			match Paused::<T>::take() {
+				Some(block) => paused_at = block,
+				None => fail!(Error::<T>::NotPaused),
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			for track in Self::track_ids() {
+				PhaseStart::<T>::mutate(track, |start| {
+					if *start <= paused_at {
+						*start = start.saturating_add(now.saturating_sub(paused_at))
+					}
+				});
+			}
+			Self::deposit_event(Event::CycleResumed { block: now });
+			Ok(())
+		}
+
+		/// Register `track` with `info`, or replace the parameters of a registered track. A
+		/// new track starts its proposal period straight away. Registering the default track
+		/// replaces the parameters set in `Config`.
+		#[pallet::weight(T::WeightInfo::register_track())]
+		pub fn register_track(
+			origin: OriginFor<T>,
+			track: TrackId,
+			info: TrackInfo<T>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			// Can the track run a cycle
+			ensure!(
+				info.proposal_period_length > 0 &&
+					info.voting_period_length > 0 &&
+					info.max_proposals > 0 &&
+					info.max_proposals <= T::MaxProposals::get() &&
+					info.selection != SelectionMode::TopK(0),
+				Error::<T>::InvalidTrack
+			);
+
+			let is_new = Self::track_info(track).is_none();
+			ensure!(
+				Tracks::<T>::contains_key(track) || Tracks::<T>::count() < T::MaxTracks::get(),
+				Error::<T>::TooManyTracks
+			);
+			Tracks::<T>::insert(track, info);
+			if is_new {
+				ProposalPeriod::<T>::insert(track, ());
+				PhaseStart::<T>::insert(track, frame_system::Pallet::<T>::block_number());
+			}
+
+			Self::deposit_event(Event::TrackRegistered { track });
+			Ok(())
+		}
+
+		/// Remove `track`, which can only be done once it has neither proposals nor voters
+		/// left. Removing the default track reverts it to the parameters set in `Config`.
+		#[pallet::weight(T::WeightInfo::remove_track())]
+		pub fn remove_track(origin: OriginFor<T>, track: TrackId) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Tracks::<T>::contains_key(track), Error::<T>::UnknownTrack);
+
+			if track != DEFAULT_TRACK {
+				ensure!(
+					Proposals::<T>::decode_len(track).unwrap_or(0) == 0 &&
+						Voters::<T>::iter_key_prefix(track).next().is_none(),
+					Error::<T>::TrackInUse
+				);
+				ProposalPeriod::<T>::remove(track);
+				CooldownPeriod::<T>::remove(track);
+				PhaseStart::<T>::remove(track);
+				Proposals::<T>::remove(track);
+				VotingRound::<T>::remove(track);
+				SessionVoters::<T>::remove(track);
+			}
+			Tracks::<T>::remove(track);
+
+			Self::deposit_event(Event::TrackRemoved { track });
+			Ok(())
+		}
+	}
+	impl<T: Config> Pallet<T> {
+		/// Refund the voters of `track` outside of its voting period and end its current
+		/// phase once it has run its length. Returns the weight used.
+		fn progress_track(track: TrackId, now: BlockNumberFor<T>) -> Weight {
+			let info;
+			match Self::track_info(track) {
+				Some(i) => info = i,
+				None => return T::WeightInfo::on_initialize_idle(),
+			}
+			let phase_over = now >= Self::phase_end(track);
+			match ProposalPeriod::<T>::contains_key(track) {
+				true => {
+					// Continue refunding the voters of the last voting period, if any are left.
+					let refunded = Self::refund_voters(track, T::MaxRefundsPerBlock::get());
+					let weight = T::WeightInfo::on_initialize_refund_voters(refunded);
+					if phase_over {
+						weight.saturating_add(Self::end_phase(track, &info, now))
+					} else {
+						weight.saturating_add(T::WeightInfo::on_initialize_idle())
+					}
+				},
+				false =>
+					if phase_over {
+						Self::end_phase(track, &info, now)
+					} else {
+						T::WeightInfo::on_initialize_idle()
+					},
+			}
+		}
+
+		/// End the current phase of `track` in block `now` and start the next one.
+		/// Returns the weight used.
+		pub(crate) fn end_phase(
+			track: TrackId,
+			info: &TrackInfo<T>,
+			now: BlockNumberFor<T>,
+		) -> Weight {
+			match Self::phase(track) {
+				Phase::Cooldown => {
+					// The cool-down has ended, proposals can be made from this block on.
+					CooldownPeriod::<T>::remove(track);
+					PhaseStart::<T>::insert(track, now);
+					Self::deposit_event(Event::CooldownPeriodEnded { track, block: now });
+					T::WeightInfo::on_initialize_end_proposal_period()
+				},
+				Phase::Proposal => {
+					// The proposal period has ended, this block and forward will not
+					// validate any new proposals or withdrawal requests.
+					ProposalPeriod::<T>::remove(track);
+					PhaseStart::<T>::insert(track, now);
+					Self::deposit_event(Event::ProposalPeriodEnded { track, block: now });
+					T::WeightInfo::on_initialize_end_proposal_period()
+				},
+				Phase::Voting => Self::end_voting_period(track, info, now),
+			}
+		}
+
+		/// End the voting period of `track` in block `now`: pick and schedule the winners,
+		/// distribute the matching pool and start refunding the voters. Returns the weight
+		/// used.
+		fn end_voting_period(
+			track: TrackId,
+			info: &TrackInfo<T>,
+			now: BlockNumberFor<T>,
+		) -> Weight {
+			// The voting period has ended, this block and forward will not
+			// validate any votes cast.
+			ProposalPeriod::<T>::insert(track, ());
+			if info.cooldown_period_length > 0 {
+				CooldownPeriod::<T>::insert(track, ());
+			}
+			PhaseStart::<T>::insert(track, now);
+			Self::deposit_event(Event::VotingPeriodEnded { track, block: now });
+			// Calculate winning proposals
+			let mut proposal_count = 0u32;
+			let mut funding_weight: Weight = 0;
+			let mut voters_weight: Weight = 0;
+			let maybe_proposals = Proposals::<T>::take(track);
+			match maybe_proposals {
+				Some(proposals) => {
+					proposal_count = proposals.len() as u32;
+					// The most votes that could have been cast on any one proposal.
+					let electorate =
+						SessionVoters::<T>::get(track).saturating_mul(T::CostModel::max_votes::<
+							T::VoteCostFunction,
+						>(
+							info.max_votes_per_account
+						));
+					let (results, ranking) = Self::select(info, &proposals, electorate);
+					let quorum: Vec<bool> =
+						results.iter().map(|(_, outcome)| outcome.reached_quorum()).collect();
+					for (p, (tally, outcome)) in proposals.iter().zip(results) {
+						Self::deposit_event(Event::ProposalTallied {
+							proposal: p.proposal,
+							tally,
+							outcome,
+						});
+					}
+					let winners: BoundedVec<VotingProposal, T::MaxProposals> =
+						BoundedVec::truncate_from(
+							ranking.into_iter().map(|i| proposals[i].clone()).collect(),
+						);
+					Self::deposit_event(Event::WinningProposals { winners: winners.clone() });
+
+					// Queue the winners, they are enacted `EnactmentPeriod` blocks
+					// from now.
+					for p in winners.iter() {
+						Self::schedule_enactment(now, &p.proposal);
+					}
+
+					// Share the matching pool between the funding proposals.
+					funding_weight = Self::distribute_matching(track, &proposals);
+
+					// The proposals are closed, return their deposits. Proposals without the
+					// support to reach the quorum lose part of their proposal deposit.
+					for (p, reached_quorum) in proposals.iter().zip(quorum) {
+						Self::release_metadata(&p.proposal, !reached_quorum);
+						CountedProposals::<T>::remove(p.proposal);
+						TrackOf::<T>::remove(p.proposal);
+						let removed = VotersOf::<T>::clear_prefix(
+							p.proposal,
+							T::MaxVotersPerSession::get(),
+							None,
+						);
+						voters_weight = voters_weight
+							.saturating_add(T::DbWeight::get().writes(removed.unique.into()));
+					}
+				},
+				None => (), //No proposals, no winners.
+			};
+
+			// Every voter is now waiting for a refund. Refund as many as fit in this
+			// block, the rest are refunded during the following blocks of the proposal
+			// period or when they claim their refund.
+			VotingRound::<T>::mutate(track, |round| *round = round.saturating_add(1));
+			SessionVoters::<T>::remove(track);
+			let refunded = Self::refund_voters(track, T::MaxRefundsPerBlock::get());
+
+			T::WeightInfo::on_initialize_end_voting_period(proposal_count, refunded)
+				.saturating_add(funding_weight)
+				.saturating_add(voters_weight)
+		}
+
+		/// Create a proposal in `track` on behalf of `creator`, see `create_proposal`.
+		/// `repeals` is the enacted proposal a repeal proposal removes, `beneficiary` the
+		/// account a funding proposal is funded to.
+		pub(crate) fn do_create_proposal(
+			creator: T::AccountId,
+			track: TrackId,
+			proposal: [u8; 32],
+			title: BoundedVec<u8, T::MaxTitleLength>,
+			description_uri: BoundedVec<u8, T::MaxDescriptionUriLength>,
+			call: Option<BoundedVec<u8, T::MaxCallLength>>,
+			repeals: Option<[u8; 32]>,
+			beneficiary: Option<T::AccountId>,
+		) -> DispatchResult {
+			// Is the track in a proposal period
+			let info;
+			match Self::track_info(track) {
+				Some(i) => info = i,
+				None => fail!(Error::<T>::UnknownTrack),
+			}
+			ensure!(!Paused::<T>::exists(), Error::<T>::CyclePaused);
+			ensure!(Self::phase(track) == Phase::Proposal, Error::<T>::NotInProposalPeriod);
+
+			// Is the creator identified
+			ensure!(
+				<<T as Config>::IdentityProvider as IdentityInterface<T::AccountId>>::is_identified(
+					&creator
+				),
+				Error::<T>::NotIdentified
+			);
+
+			// Does the proposal exist already
+			ensure!(
+				!CountedProposals::<T>::contains_key(proposal),
+				Error::<T>::ProposalAlreadyExists
+			);
+
+			// Does the track allow for more proposals to be added
+			ensure!(
+				(Proposals::<T>::decode_len(track).unwrap_or(0) as u32) < info.max_proposals,
+				Error::<T>::TooManyProposals
+			);
+
+			// Is the proposal the preimage of the call it carries, and can the call be enacted
+			if let Some(call) = &call {
+				ensure!(sp_io::hashing::blake2_256(call) == proposal, Error::<T>::PreimageMismatch);
+				match <T as Config>::Proposal::decode(&mut &call[..]) {
+					Ok(call) => {
+						ensure!(T::ProposalFilter::contains(&call), Error::<T>::CallFiltered);
+						ensure!(
+							call.get_dispatch_info().weight <= T::MaxCallWeight::get(),
+							Error::<T>::CallTooHeavy
+						)
+					},
+					Err(_) => fail!(Error::<T>::UndecodableCall),
+				}
+			}
+
+			// Reserve the deposits for the metadata and the proposal itself
+			let bytes = title.len() + description_uri.len() + call.as_ref().map_or(0, |c| c.len());
+			let deposit = Self::metadata_deposit(bytes as u32)?;
+			let proposal_deposit = T::ProposalDeposit::get();
+			match deposit.checked_add(&proposal_deposit) {
+				Some(total) => T::Currency::reserve(&creator, total)?,
+				None => fail!(Error::<T>::MathError),
+			}
+
+			// Cool, continue with storage entry. `Proposals` is appended to first so
+			// that a failure leaves neither storage item modified.
+			Proposals::<T>::try_append(
+				track,
+				VotingProposal { proposal, votes_for: 0, votes_against: 0 },
+			)
+			.map_err(|_| Error::<T>::TooManyProposals)?;
+			CountedProposals::<T>::insert(proposal, creator.clone());
+			TrackOf::<T>::insert(proposal, track);
+			MetadataOf::<T>::insert(
+				proposal,
+				ProposalMetadata {
+					title,
+					description_uri,
+					call,
+					repeals,
+					depositor: creator,
+					deposit,
+					proposal_deposit,
+				},
+			);
+			if let Some(beneficiary) = beneficiary {
+				FundingOf::<T>::insert(
+					proposal,
+					Funding { beneficiary, contributions: BoundedVec::default() },
+				);
+			}
+
+			Self::deposit_event(Event::ProposalCreated { proposal });
+			Ok(())
+		}
+
+		/// Remove a proposal from both `CountedProposals` and the `Proposals` of its track.
+		/// Every path that removes a single proposal has to go through here so
+		/// that the two storage items never disagree on which proposals are live.
+		/// See `release_metadata` for `slash`.
+		pub(crate) fn remove_proposal(proposal: &[u8; 32], slash: bool) {
+			let track = TrackOf::<T>::take(proposal);
+			CountedProposals::<T>::remove(proposal);
+			ProposalVoters::<T>::remove(proposal);
+			let _ = VotersOf::<T>::clear_prefix(proposal, T::MaxVotersPerSession::get(), None);
+			FundingOf::<T>::remove(proposal);
+			Proposals::<T>::mutate(track, |maybe_proposals| {
+				if let Some(proposals) = maybe_proposals {
+					proposals.retain(|p| p.proposal != *proposal);
+				}
+			});
+			Self::release_metadata(proposal, slash);
+		}
+
+		/// Refund the voters of the current round what they reserved for their votes on
+		/// `proposal` and drop those votes. Only the accounts in `VotersOf` are looked at,
+		/// returns how many there were.
+		pub(crate) fn refund_votes_on(proposal: &[u8; 32]) -> Result<u32, DispatchError> {
+			let track = TrackOf::<T>::get(proposal);
+			let round = VotingRound::<T>::get(track);
+			let voters: Vec<T::AccountId> =
+				VotersOf::<T>::drain_prefix(proposal).map(|(who, _)| who).collect();
+			let count = voters.len() as u32;
+			for who in voters {
+				let mut voter;
+				match Voters::<T>::get(track, &who) {
+					Some(v) if v.round == round => voter = v,
+					_ => continue,
+				}
+				let votes = voter.votes_on(proposal);
+				if votes.total() == 0 {
+					continue
+				}
+				let refund = Self::vote_reserve(votes.total())?;
+				let freed = Self::vote_spend(votes.total()).ok_or(Error::<T>::MathError)?;
+				voter.amount_reserved = voter.amount_reserved.saturating_sub(refund);
+				voter.spent = voter.spent.saturating_sub(freed);
+				voter.set_votes_on(*proposal, VoteRecord::default())?;
+				T::Currency::unreserve(&who, refund);
+				Voters::<T>::insert(track, &who, voter);
+			}
+			Ok(count)
+		}
+
+		/// Remove the metadata of a proposal and return its deposits. With `slash`, the
+		/// `DepositSlash` share of the proposal deposit goes to `Slash` instead.
+		pub(crate) fn release_metadata(proposal: &[u8; 32], slash: bool) {
+			if let Some(metadata) = MetadataOf::<T>::take(proposal) {
+				let mut refund = metadata.deposit.saturating_add(metadata.proposal_deposit);
+				if slash {
+					let amount = T::DepositSlash::get() * metadata.proposal_deposit;
+					let (imbalance, _) = T::Currency::slash_reserved(&metadata.depositor, amount);
+					T::Slash::on_unbalanced(imbalance);
+					refund = refund.saturating_sub(amount);
+					Self::deposit_event(Event::DepositSlashed {
+						proposal: *proposal,
+						who: metadata.depositor.clone(),
+						amount,
+					});
+				}
+				T::Currency::unreserve(&metadata.depositor, refund);
+			}
+		}
+
+		/// Queue a winning proposal to be enacted `EnactmentPeriod` blocks after `now`.
+		/// If it can not be scheduled it is left pending, for root to enact it with `enact`
+		/// or to veto it with `cancel_proposal`. Its call is never dispatched right away.
+		pub(crate) fn schedule_enactment(now: BlockNumberFor<T>, proposal: &[u8; 32]) {
+			// A proposal that won an earlier round and is still waiting is already queued.
+			if PendingEnactments::<T>::contains_key(proposal) {
+				return
+			}
+			let when = now.saturating_add(T::EnactmentPeriod::get());
+			let (call, repeals) = match MetadataOf::<T>::get(proposal) {
+				Some(metadata) => (metadata.call, metadata.repeals),
+				None => (None, None),
+			};
+			PendingEnactments::<T>::insert(proposal, PendingEnactment { when, call, repeals });
+			match T::Scheduler::schedule_named(
+				(ENACTMENT_ID, proposal).encode(),
+				DispatchTime::At(when),
+				None,
+				63,
+				frame_system::RawOrigin::Root.into(),
+				Call::<T>::enact { proposal: *proposal }.into(),
+			) {
+				Ok(_) =>
+					Self::deposit_event(Event::ProposalScheduled { proposal: *proposal, when }),
+				Err(_) => Self::deposit_event(Event::ProposalNotScheduled { proposal: *proposal }),
+			}
+		}
+
+		/// Record a pending proposal in `EnactedProposals` and dispatch the call it carries,
+		/// if any, with `EnactmentOrigin`. A repeal proposal instead removes its target from
+		/// `EnactedProposals`. Returns the weight used by the call.
+		pub(crate) fn do_enact(proposal: &[u8; 32]) -> Weight {
+			let pending;
+			match PendingEnactments::<T>::take(proposal) {
+				Some(p) => pending = p,
+				None => return 0,
+			}
+			if let Some(target) = pending.repeals {
+				EnactedProposals::<T>::remove(target);
+				Self::deposit_event(Event::ProposalRepealed { proposal: *proposal, target });
+				return 0
+			}
+			EnactedProposals::<T>::insert(proposal, ());
+			let encoded;
+			match pending.call {
+				Some(call) => encoded = call,
+				None => {
+					Self::deposit_event(Event::ProposalEnacted {
+						proposal: *proposal,
+						result: Ok(()),
+					});
+					return 0
+				},
+			}
+			// The call was decoded and filtered when the proposal was created, but a runtime
+			// upgrade since then may have changed what it decodes to or what is let through.
+			match <T as Config>::Proposal::decode(&mut &encoded[..]) {
+				Ok(call) if !T::ProposalFilter::contains(&call) => {
+					Self::deposit_event(Event::ProposalEnacted {
+						proposal: *proposal,
+						result: Err(Error::<T>::CallFiltered.into()),
+					});
+					0
+				},
+				Ok(call) => {
+					let info = call.get_dispatch_info();
+					let result = call.dispatch(T::EnactmentOrigin::get().into());
+					Self::deposit_event(Event::ProposalEnacted {
+						proposal: *proposal,
+						result: result.map(|_| ()).map_err(|e| e.error),
+					});
+					extract_actual_weight(&result, &info)
+				},
+				Err(_) => {
+					Self::deposit_event(Event::ProposalEnacted {
+						proposal: *proposal,
+						result: Err(Error::<T>::UndecodableCall.into()),
+					});
+					0
+				},
+			}
+		}
+
+		/// The account of the matching pool of `track`. The default track uses the account of
+		/// `Config::PalletId`, every other track a sub-account of it, so no track can empty
+		/// the pool of another.
+		pub fn matching_pool_account(track: TrackId) -> T::AccountId {
+			match track {
+				DEFAULT_TRACK => T::PalletId::get().into_account_truncating(),
+				track => T::PalletId::get().into_sub_account_truncating(track),
+			}
+		}
+
+		/// Share the matching pool of `track` between the funding proposals among `proposals`
+		/// by quadratic funding and transfer every share to the proposal's beneficiary.
+		/// What is left from rounding stays in the pool. Returns the weight used.
+		pub(crate) fn distribute_matching(track: TrackId, proposals: &[VotingProposal]) -> Weight {
+			let fundings: Vec<([u8; 32], Funding<T>)> = proposals
+				.iter()
+				.filter_map(|p| FundingOf::<T>::take(p.proposal).map(|f| (p.proposal, f)))
+				.collect();
+			let weight = T::WeightInfo::on_initialize_distribute_matching(fundings.len() as u32);
+
+			// The match of every proposal: (Σ √c)² - Σ c
+			let matches: Vec<BalanceOf<T>> = fundings
+				.iter()
+				.map(|(_, funding)| {
+					let (sum_sqrt, sum) = funding.contributions.iter().fold(
+						(BalanceOf::<T>::zero(), BalanceOf::<T>::zero()),
+						|(sum_sqrt, sum), (_, c)| {
+							(sum_sqrt.saturating_add(c.integer_sqrt()), sum.saturating_add(*c))
+						},
+					);
+					sum_sqrt.saturating_mul(sum_sqrt).saturating_sub(sum)
+				})
+				.collect();
+			let total_match =
+				matches.iter().fold(BalanceOf::<T>::zero(), |acc, m| acc.saturating_add(*m));
+			if total_match.is_zero() {
+				return weight
+			}
+
+			let pool = Self::matching_pool_account(track);
+			let available =
+				T::Currency::free_balance(&pool).saturating_sub(T::Currency::minimum_balance());
+			for ((proposal, funding), m) in fundings.into_iter().zip(matches) {
+				let amount = Perbill::from_rational(m, total_match) * available;
+				if amount.is_zero() {
+					continue
+				}
+				if T::Currency::transfer(
+					&pool,
+					&funding.beneficiary,
+					amount,
+					ExistenceRequirement::KeepAlive,
+				)
+				.is_ok()
+				{
+					Self::deposit_event(Event::MatchingDistributed {
+						proposal,
+						beneficiary: funding.beneficiary,
+						amount,
+					});
+				}
+			}
+			weight
+		}
+
+		/// The hash a proposal to repeal `target` is addressed by.
+		pub fn repeal_hash(target: &[u8; 32]) -> [u8; 32] {
+			sp_io::hashing::blake2_256(&(b"repeal", target).encode())
+		}
+
+		/// The deposit reserved for `bytes` bytes of proposal metadata.
+		pub fn metadata_deposit(bytes: u32) -> Result<BalanceOf<T>, Error<T>> {
+			let bytes: BalanceOf<T> = bytes.into();
+			bytes
+				.checked_mul(&T::MetadataDepositPerByte::get())
+				.and_then(|per_byte| per_byte.checked_add(&T::MetadataDepositBase::get()))
+				.ok_or(Error::<T>::MathError)
+		}
+
+		/// The amount reserved for holding `votes` votes on a single proposal.
+		pub fn vote_cost(votes: u32) -> Result<BalanceOf<T>, Error<T>> {
+			T::VoteCostFunction::cost(votes)
+				.and_then(|cost| BalanceOf::<T>::try_from(cost).ok())
+				.and_then(|cost| cost.checked_mul(&T::VoteCostUnit::get()))
+				.ok_or(Error::<T>::MathError)
+		}
+
+		/// The part of the vote budget spent by holding `votes` votes on a single proposal
+		/// under the `CostModel`. `None` on overflow.
+		pub fn vote_spend(votes: u32) -> Option<u32> {
+			T::CostModel::spend::<T::VoteCostFunction>(votes)
+		}
+
+		/// The amount reserved for holding `votes` votes on a single proposal under the
+		/// `CostModel`, nothing if votes are not paid for with funds.
+		pub fn vote_reserve(votes: u32) -> Result<BalanceOf<T>, Error<T>> {
+			match T::CostModel::reserves_funds() {
+				true => Self::vote_cost(votes),
+				false => Ok(BalanceOf::<T>::zero()),
+			}
+		}
+
+		/// The parameters `track` runs with, `None` if there is no such track.
+		pub fn track_info(track: TrackId) -> Option<TrackInfo<T>> {
+			match Tracks::<T>::get(track) {
+				Some(info) => Some(info),
+				None if track == DEFAULT_TRACK => Some(Self::default_track_info()),
+				None => None,
+			}
+		}
+
+		/// The parameters of the default track as set in `Config`.
+		pub fn default_track_info() -> TrackInfo<T> {
+			TrackInfo {
+				name: BoundedVec::default(),
+				proposal_period_length: T::ProposalPeriodLength::get(),
+				voting_period_length: T::VotingPeriodLength::get(),
+				cooldown_period_length: T::CooldownPeriodLength::get(),
+				max_proposals: T::MaxProposals::get(),
+				max_votes_per_account: T::MaxVotesPerAccount::get(),
+				minimum_turnout: T::MinimumTurnout::get(),
+				minimum_voters: T::MinimumVoters::get(),
+				threshold: None,
+				selection: T::Selection::get(),
+			}
+		}
+
+		/// Every track with its name, the default track first.
+		pub fn tracks() -> Vec<(TrackId, Vec<u8>)> {
+			Self::track_ids()
+				.into_iter()
+				.filter_map(|track| Some((track, Self::track_info(track)?.name.into_inner())))
+				.collect()
+		}
+
+		/// The default track followed by every other registered track.
+		pub fn track_ids() -> Vec<TrackId> {
+			let mut tracks = Vec::from([DEFAULT_TRACK]);
+			tracks.extend(Tracks::<T>::iter_keys().filter(|track| *track != DEFAULT_TRACK));
+			tracks
+		}
+
+		/// The phase `track` is currently in.
+		pub fn phase(track: TrackId) -> Phase {
+			match (
+				ProposalPeriod::<T>::contains_key(track),
+				CooldownPeriod::<T>::contains_key(track),
+			) {
+				(true, true) => Phase::Cooldown,
+				(true, false) => Phase::Proposal,
+				(false, _) => Phase::Voting,
+			}
+		}
+
+		/// The block the current phase of `track` ends in, in its `on_initialize`.
+		pub fn phase_end(track: TrackId) -> BlockNumberFor<T> {
+			let length = match Self::track_info(track) {
+				Some(info) => match Self::phase(track) {
+					Phase::Proposal => info.proposal_period_length,
+					Phase::Voting => info.voting_period_length,
+					Phase::Cooldown => info.cooldown_period_length,
+				},
+				None => 0,
+			};
+			PhaseStart::<T>::get(track).saturating_add(length.into())
+		}
+
+		/// The number of blocks from `now` until the current phase of `track` ends.
+		pub fn blocks_remaining(track: TrackId, now: BlockNumberFor<T>) -> BlockNumberFor<T> {
+			Self::phase_end(track).saturating_sub(now)
+		}
+
+		/// Whether a proposal on a track with `info` and `tally` wins, checking the quorum
+		/// before the threshold. `electorate` is the most votes that could have been cast on
+		/// the proposal.
+		pub fn outcome(info: &TrackInfo<T>, tally: &Tally, electorate: u32) -> Outcome {
+			let approved = match info.threshold {
+				Some(rule) => rule.approved(tally, electorate),
+				None => T::Threshold::approved(tally, electorate),
+			};
+			if tally.turnout() < info.minimum_turnout {
+				Outcome::TurnoutTooLow
+			} else if tally.voters < info.minimum_voters {
+				Outcome::TooFewVoters
+			} else if approved {
+				Outcome::Approved
+			} else {
+				Outcome::ThresholdNotMet
+			}
+		}
+
+		/// Tally the proposals of a finished voting period of a track with `info` and pick the
+		/// winners according to its `selection`. Takes the voter counts of the proposals out
+		/// of `ProposalVoters`. Returns the tally and outcome of every proposal, in the order
+		/// of `proposals`, and the indices of the winners in the order they were picked.
+		pub(crate) fn select(
+			info: &TrackInfo<T>,
+			proposals: &[VotingProposal],
+			electorate: u32,
+		) -> (Vec<(Tally, Outcome)>, Vec<usize>) {
+			let mut results: Vec<(Tally, Outcome)> = proposals
+				.iter()
+				.map(|p| {
+					let tally = Tally {
+						votes_for: p.votes_for,
+						votes_against: p.votes_against,
+						voters: ProposalVoters::<T>::take(p.proposal),
+					};
+					(tally, Self::outcome(info, &tally, electorate))
+				})
+				.collect();
+			let mut ranking: Vec<usize> =
+				(0..results.len()).filter(|i| results[*i].1 == Outcome::Approved).collect();
+
+			if let SelectionMode::TopK(k) = info.selection {
+				// Highest score first, then most votes for, then the proposal created first.
+				// `Proposals` keeps the order proposals were created in.
+				ranking.sort_by_key(|i| {
+					let tally = &results[*i].0;
+					(sp_std::cmp::Reverse(tally.score()), sp_std::cmp::Reverse(tally.votes_for), *i)
+				});
+				for i in ranking.iter().skip(k as usize) {
+					results[*i].1 = Outcome::OutRanked;
+				}
+				ranking.truncate(k as usize);
+			}
+			(results, ranking)
+		}
+
+		/// The live tally of every proposal of `track` in the current round.
+		pub fn tallies(track: TrackId) -> Vec<VotingProposal> {
+			Proposals::<T>::get(track)
+				.map(|proposals| proposals.into_inner())
+				.unwrap_or_default()
+		}
+
+		/// The amount `who` would additionally have reserved by casting `votes_for` and
+		/// `votes_against` on `proposal` in the current voting round of its track. Votes that
+		/// lower the position of `who` cost nothing. Returns `None` if the vote would be
+		/// rejected for exceeding the vote budget of the track or overflowing.
+		pub fn quote_vote(
+			who: &T::AccountId,
+			proposal: &[u8; 32],
+			votes_for: u32,
+			votes_against: u32,
+		) -> Option<BalanceOf<T>> {
+			let track = TrackOf::<T>::try_get(proposal).ok()?;
+			let info = Self::track_info(track)?;
+			let (old_votes, spent, is_new_voter) = match Voters::<T>::get(track, who) {
+				Some(voter) if voter.round == VotingRound::<T>::get(track) =>
+					(voter.votes_on(proposal), voter.spent, false),
+				_ => (VoteRecord::default(), 0, true),
+			};
+			let new_votes = old_votes.add(votes_for, votes_against)?;
+			if is_new_voter && new_votes.total() == 0 {
+				return None
+			}
+			let new_spent = spent
+				.checked_sub(Self::vote_spend(old_votes.total())?)?
+				.checked_add(Self::vote_spend(new_votes.total())?)?;
+			if new_spent > T::CostModel::budget(who, info.max_votes_per_account) {
+				return None
+			}
+			let old_reserve = Self::vote_reserve(old_votes.total()).ok()?;
+			let new_reserve = Self::vote_reserve(new_votes.total()).ok()?;
+			Some(new_reserve.saturating_sub(old_reserve))
+		}
+
+		/// The votes `who` has cast in the current voting round of every track, per proposal.
+		pub fn votes_of(who: &T::AccountId) -> Vec<([u8; 32], VoteRecord)> {
+			Self::track_ids()
+				.into_iter()
+				.filter_map(|track| match Voters::<T>::get(track, who) {
+					Some(voter) if voter.round == VotingRound::<T>::get(track) =>
+						Some(voter.votes_per_proposal.into_inner()),
+					_ => None,
+				})
+				.flatten()
+				.collect()
+		}
+
+		/// The amount `who` currently has reserved for votes on every track, including any
+		/// reserve from an earlier round that has not been refunded yet.
+		pub fn reserve_of(who: &T::AccountId) -> BalanceOf<T> {
+			Self::track_ids().into_iter().fold(
+				BalanceOf::<T>::zero(),
+				|total, track| match Voters::<T>::get(track, who) {
+					Some(voter) => total.saturating_add(voter.amount_reserved),
+					None => total,
+				},
+			)
+		}
+
+		/// Refund the reserve of and remove up to `limit` voters of `track`.
+		/// Returns the number of voters that were refunded.
+		/// Must only be called outside of the voting period of the track, when every
+		/// voter is from an earlier round.
+		pub(crate) fn refund_voters(track: TrackId, limit: u32) -> u32 {
+			let voters: Vec<T::AccountId> =
+				Voters::<T>::iter_key_prefix(track).take(limit as usize).collect();
+			for who in voters.iter() {
+				if let Some(voter) = Voters::<T>::take(track, who) {
+					T::Currency::unreserve(who, voter.amount_reserved);
+				}
+			}
+			voters.len() as u32
+		}
+
+		/// Check that the `Proposals` of every track and `CountedProposals` describe the same
+		/// set of proposals, that `VotersOf` lists the voters counted in `ProposalVoters` and
+		/// that metadata is only kept for live proposals.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let mut proposals = Vec::new();
+			for (track, track_proposals) in Proposals::<T>::iter() {
+				ensure!(
+					Self::track_info(track).is_some(),
+					"Proposals are kept for an unknown track"
+				);
+				for p in track_proposals.iter() {
+					ensure!(
+						CountedProposals::<T>::contains_key(p.proposal),
+						"Proposal is missing from CountedProposals"
+					);
+					ensure!(
+						TrackOf::<T>::get(p.proposal) == track,
+						"Proposal is stored in the Proposals of another track"
+					);
+					ensure!(
+						!proposals.contains(&p.proposal),
+						"Proposal is stored more than once in Proposals"
+					);
+					proposals.push(p.proposal);
+				}
+			}
+			ensure!(
+				proposals.len() as u32 == CountedProposals::<T>::count(),
+				"Proposals and CountedProposals are out of sync"
+			);
+			for proposal in TrackOf::<T>::iter_keys() {
+				ensure!(
+					proposals.contains(&proposal),
+					"Track is kept for a proposal that is not live"
+				);
+			}
+			for proposal in ProposalVoters::<T>::iter_keys() {
+				ensure!(
+					proposals.contains(&proposal),
+					"Voters are counted for a proposal that is not live"
+				);
+			}
+			for proposal in proposals.iter() {
+				ensure!(
+					VotersOf::<T>::iter_key_prefix(proposal).count() as u32 ==
+						ProposalVoters::<T>::get(proposal),
+					"ProposalVoters and VotersOf are out of sync"
+				);
+			}
+			for proposal in VotersOf::<T>::iter().map(|(proposal, _, _)| proposal) {
+				ensure!(
+					proposals.contains(&proposal),
+					"Voters are kept for a proposal that is not live"
+				);
+			}
+			for proposal in FundingOf::<T>::iter_keys() {
+				ensure!(
+					proposals.contains(&proposal),
+					"Funding is kept for a proposal that is not live"
+				);
+			}
+			for proposal in MetadataOf::<T>::iter_keys() {
+				ensure!(
+					proposals.contains(&proposal),
+					"Metadata is kept for a proposal that is not live"
+				);
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> IdentityInUse<T::AccountId> for Pallet<T> {
+		/// With voice credits every identity brings a budget of its own, the identity has to
+		/// stay until the votes it paid for in a running round are tallied.
+		fn in_use(who: &T::AccountId) -> bool {
+			!T::CostModel::reserves_funds() &&
+				Self::track_ids().into_iter().any(|track| {
+					Voters::<T>::get(track, who).map_or(false, |voter| {
+						voter.round == VotingRound::<T>::get(track) && voter.spent > 0
+					})
+				})
+		}
 	}
 }
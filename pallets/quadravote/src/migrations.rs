@@ -0,0 +1,254 @@
+//! Storage migrations for the quadravote pallet.
+
+/// The storage of the single cycle the pallet ran before tracks, up to storage version 2.
+pub(crate) mod single_track {
+	use crate::{Config, Pallet, Voter, VotingProposal};
+	use frame_support::{pallet_prelude::*, storage_alias, BoundedVec};
+	use frame_system::pallet_prelude::BlockNumberFor;
+	use sp_std::vec::Vec;
+
+	#[storage_alias]
+	pub(crate) type ProposalPeriod<T: Config> = StorageValue<Pallet<T>, ()>;
+
+	#[storage_alias]
+	pub(crate) type CooldownPeriod<T: Config> = StorageValue<Pallet<T>, ()>;
+
+	#[storage_alias]
+	pub(crate) type PhaseStart<T: Config> = StorageValue<Pallet<T>, BlockNumberFor<T>, ValueQuery>;
+
+	#[storage_alias]
+	pub(crate) type LeftoverProposalCursor<T: Config> = StorageValue<Pallet<T>, Vec<u8>>;
+
+	#[storage_alias]
+	pub(crate) type VotingRound<T: Config> = StorageValue<Pallet<T>, u32, ValueQuery>;
+
+	#[storage_alias]
+	pub(crate) type SessionVoters<T: Config> = StorageValue<Pallet<T>, u32, ValueQuery>;
+
+	/// `Voters` was a counted map, `CounterForVoters` is its counter.
+	#[storage_alias]
+	pub(crate) type Voters<T: Config> =
+		StorageMap<Pallet<T>, Blake2_128Concat, <T as frame_system::Config>::AccountId, Voter<T>>;
+
+	#[storage_alias]
+	pub(crate) type CounterForVoters<T: Config> = StorageValue<Pallet<T>, u32, ValueQuery>;
+
+	#[storage_alias]
+	pub(crate) type Proposals<T: Config> =
+		StorageValue<Pallet<T>, BoundedVec<VotingProposal, <T as Config>::MaxProposals>>;
+}
+
+pub mod v1 {
+	use super::single_track::{CounterForVoters, Proposals, Voters};
+	use crate::{BalanceOf, Config, Pallet};
+	use codec::Decode;
+	use frame_support::{
+		traits::{Get, GetStorageVersion, OnRuntimeUpgrade, ReservableCurrency, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::{Saturating, Zero};
+	use sp_std::vec::Vec;
+
+	/// A voter as stored before votes were tracked per direction and keyed by proposal hash.
+	/// `votes_per_proposal` holds the sum of votes for and against, indexed by the position
+	/// of the proposal in `Proposals`.
+	#[derive(Decode)]
+	struct OldVoter<Balance> {
+		#[allow(dead_code)]
+		total_votes: u32,
+		#[allow(dead_code)]
+		amount_reserved: Balance,
+		votes_per_proposal: Vec<u32>,
+	}
+
+	/// Migrate `Voters` to storage version 1.
+	///
+	/// Old entries can not be translated faithfully, the direction of each vote was never
+	/// recorded and votes were priced in whole balance units. Instead every old voter is
+	/// refunded what `cast_vote` reserved for them and the tallies of the running round are
+	/// reset, so votes can be cast again under the new rules.
+	pub struct MigrateToV1<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated = 0u64;
+			Voters::<T>::translate::<OldVoter<BalanceOf<T>>, _>(|who, old| {
+				translated += 1;
+				// `cast_vote` reserved the square of the votes on every proposal.
+				let reserved =
+					old.votes_per_proposal.iter().fold(BalanceOf::<T>::zero(), |acc, votes| {
+						let votes: BalanceOf<T> = (*votes).into();
+						acc.saturating_add(votes.saturating_mul(votes))
+					});
+				T::Currency::unreserve(&who, reserved);
+				None
+			});
+			CounterForVoters::<T>::kill();
+
+			Proposals::<T>::mutate(|maybe_proposals| {
+				if let Some(proposals) = maybe_proposals {
+					for p in proposals.iter_mut() {
+						p.votes_for = 0;
+						p.votes_against = 0;
+					}
+				}
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(translated + 2, translated * 2 + 3)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"Quadravote storage version was not updated"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v2 {
+	use super::single_track::PhaseStart;
+	use crate::{Config, Pallet};
+	use frame_support::{
+		traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use frame_system::pallet_prelude::BlockNumberFor;
+	use sp_runtime::traits::{Saturating, Zero};
+
+	/// Migrate to storage version 2, which stores the block the current phase started in
+	/// instead of switching phases at every multiple of the period length.
+	///
+	/// `OldPeriodLength` is the `PeriodLength` the pallet was configured with before, the
+	/// current phase is taken to have started at the last multiple of it. The new period
+	/// lengths apply straight away, the current phase included: it ends once the new length
+	/// of its period has passed since that start, in the block of the upgrade itself if that
+	/// length has already passed. A zero `OldPeriodLength` starts the current phase in the block of
+	/// the upgrade.
+	pub struct MigrateToV2<T, OldPeriodLength>(sp_std::marker::PhantomData<(T, OldPeriodLength)>);
+
+	impl<T: Config, OldPeriodLength: Get<u32>> OnRuntimeUpgrade for MigrateToV2<T, OldPeriodLength> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let period: BlockNumberFor<T> = OldPeriodLength::get().into();
+			// The old pallet switched phases in `on_initialize` at every multiple of the
+			// period, which runs after this migration. At a multiple the phase that is
+			// about to end started a full period ago.
+			let start = match period.is_zero() {
+				true => now,
+				false => match now % period {
+					rest if rest.is_zero() => now.saturating_sub(period),
+					rest => now - rest,
+				},
+			};
+			PhaseStart::<T>::put(start);
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 2,
+				"Quadravote storage version was not updated"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v3 {
+	use super::single_track;
+	use crate::{
+		Config, CooldownPeriod, CountedProposals, Pallet, PhaseStart, ProposalPeriod, Proposals,
+		SessionVoters, TrackOf, Voter, Voters, VotersOf, VotingRound, DEFAULT_TRACK,
+	};
+	use frame_support::{
+		traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_std::vec::Vec;
+
+	/// Migrate to storage version 3, which runs a cycle per track.
+	///
+	/// The single cycle the pallet ran so far becomes the cycle of the default track: its
+	/// phase, proposals, voters and round are moved over as they are. Proposals that were
+	/// withdrawn but left behind in `Proposals` are dropped.
+	pub struct MigrateToV3<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			// The old values are stored at the prefix of the new maps, so every value is
+			// taken before its map is written to.
+			if single_track::ProposalPeriod::<T>::take().is_some() {
+				ProposalPeriod::<T>::insert(DEFAULT_TRACK, ());
+			}
+			if single_track::CooldownPeriod::<T>::take().is_some() {
+				CooldownPeriod::<T>::insert(DEFAULT_TRACK, ());
+			}
+			PhaseStart::<T>::insert(DEFAULT_TRACK, single_track::PhaseStart::<T>::take());
+			VotingRound::<T>::insert(DEFAULT_TRACK, single_track::VotingRound::<T>::take());
+			SessionVoters::<T>::insert(DEFAULT_TRACK, single_track::SessionVoters::<T>::take());
+			// `CountedProposals` is only ever cleared in full in one go, so a cursor is
+			// never left behind.
+			single_track::LeftoverProposalCursor::<T>::kill();
+
+			let mut proposals = 0u64;
+			if let Some(mut old) = single_track::Proposals::<T>::take() {
+				proposals = old.len() as u64;
+				// Older versions of the pallet could leave a withdrawn proposal in
+				// `Proposals`, do not make it votable again.
+				old.retain(|p| CountedProposals::<T>::contains_key(p.proposal));
+				for p in old.iter() {
+					TrackOf::<T>::insert(p.proposal, DEFAULT_TRACK);
+				}
+				Proposals::<T>::insert(DEFAULT_TRACK, old);
+			}
+
+			let voters: Vec<(T::AccountId, Voter<T>)> =
+				single_track::Voters::<T>::drain().collect();
+			let moved = voters.len() as u64;
+			let round = VotingRound::<T>::get(DEFAULT_TRACK);
+			let mut listed = 0u64;
+			for (who, voter) in voters {
+				// `VotersOf` is new, list the voters of the current round under the proposals
+				// they hold votes on.
+				for proposal in voter.proposals_in(round) {
+					VotersOf::<T>::insert(proposal, &who, ());
+					listed += 1;
+				}
+				Voters::<T>::insert(DEFAULT_TRACK, who, voter);
+			}
+			single_track::CounterForVoters::<T>::kill();
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get()
+				.reads_writes(moved + proposals + 10, moved * 2 + proposals + listed + 11)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 3,
+				"Quadravote storage version was not updated"
+			);
+			Pallet::<T>::do_try_state()
+		}
+	}
+}
@@ -1,22 +1,31 @@
 use crate as pallet_quadravote;
+use crate::{VoteCostFunction, VoteCostModel, DEFAULT_TRACK};
 use frame_support::{
-	dispatch::Vec,
+	dispatch::{DispatchResult, Vec},
 	parameter_types,
-	traits::{ConstU64, Everything, OnFinalize, OnInitialize},
+	traits::{
+		ConstU32, ConstU64, Contains, EqualPrivilegeOnly, Everything, OnFinalize, OnInitialize,
+	},
+	PalletId,
 };
 use frame_system as system;
+use frame_system::EnsureRoot;
 use pallet_balances;
 use pallet_votingregistry;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
 pub(crate) type AccountId = u64;
+
+// The length of the proposal and voting periods, unless a test changes them.
+pub(crate) const PERIOD_LENGTH: u32 = 5;
 type Balance = u64;
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
@@ -29,6 +38,7 @@ frame_support::construct_runtime!(
 		Balances: pallet_balances,
 		Quadravote: pallet_quadravote,
 		VotingRegistry: pallet_votingregistry,
+		Scheduler: pallet_scheduler,
 	}
 );
 
@@ -66,10 +76,85 @@ impl system::Config for Test {
 
 // Quadravote parameter types
 parameter_types! {
-	pub const PeriodLength: u32 = 5;
+	pub static ProposalPeriodLength: u32 = PERIOD_LENGTH;
+	pub static VotingPeriodLength: u32 = PERIOD_LENGTH;
+	pub static CooldownPeriodLength: u32 = 0;
 	pub const MaxProposals: u8 = 10;
 	pub const MaxVotesPerAccount: u32 = 10;
 	pub const MaxVotersPerSession: u32 = 10;
+	pub const MaxRefundsPerBlock: u32 = 1;
+	pub const MaxTitleLength: u32 = 16;
+	pub const MaxDescriptionUriLength: u32 = 32;
+	pub const MaxCallLength: u32 = 64;
+	pub EnactmentOrigin: frame_system::RawOrigin<AccountId> = frame_system::RawOrigin::Root;
+	pub const MaxCallWeight: u64 = 1_000_000_000;
+	pub const EnactmentPeriod: u64 = 2;
+	pub const QuadravotePalletId: PalletId = PalletId(*b"py/qvote");
+	pub const MaxContributors: u32 = 10;
+	pub static ProposalDeposit: u64 = 0;
+	pub const DepositSlash: Perbill = Perbill::from_percent(50);
+	pub static MinimumTurnout: u32 = 1;
+	pub static MinimumVoters: u32 = 1;
+	pub static Selection: pallet_quadravote::SelectionMode =
+		pallet_quadravote::SelectionMode::Majority;
+	pub static UseVoiceCredits: bool = false;
+	pub static LinearVoteCost: bool = false;
+}
+
+// Pays for votes with reserved funds, or with voice credits when `UseVoiceCredits` is set.
+pub struct CostModel;
+type Funds = pallet_quadravote::ReserveFunds;
+type Credits = pallet_quadravote::VoiceCredits<VotingRegistry>;
+
+impl VoteCostModel<AccountId> for CostModel {
+	fn reserves_funds() -> bool {
+		match UseVoiceCredits::get() {
+			true => <Credits as VoteCostModel<AccountId>>::reserves_funds(),
+			false => <Funds as VoteCostModel<AccountId>>::reserves_funds(),
+		}
+	}
+
+	fn budget(who: &AccountId, max_votes_per_account: u32) -> u32 {
+		match UseVoiceCredits::get() {
+			true => Credits::budget(who, max_votes_per_account),
+			false => Funds::budget(who, max_votes_per_account),
+		}
+	}
+
+	fn spend<Cost: VoteCostFunction>(votes: u32) -> Option<u32> {
+		match UseVoiceCredits::get() {
+			true => <Credits as VoteCostModel<AccountId>>::spend::<Cost>(votes),
+			false => <Funds as VoteCostModel<AccountId>>::spend::<Cost>(votes),
+		}
+	}
+
+	fn max_votes<Cost: VoteCostFunction>(max_votes_per_account: u32) -> u32 {
+		match UseVoiceCredits::get() {
+			true => <Credits as VoteCostModel<AccountId>>::max_votes::<Cost>(max_votes_per_account),
+			false => <Funds as VoteCostModel<AccountId>>::max_votes::<Cost>(max_votes_per_account),
+		}
+	}
+}
+
+// Proposals can carry any call but a runtime upgrade.
+pub struct ProposalFilter;
+
+impl Contains<Call> for ProposalFilter {
+	fn contains(call: &Call) -> bool {
+		!matches!(call, Call::System(frame_system::Call::set_code { .. }))
+	}
+}
+
+// Prices votes linearly when `LinearVoteCost` is set, quadratically otherwise.
+pub struct VoteCost;
+
+impl VoteCostFunction for VoteCost {
+	fn cost(votes: u32) -> Option<u128> {
+		match LinearVoteCost::get() {
+			true => pallet_quadravote::Linear::cost(votes),
+			false => pallet_quadravote::Quadratic::cost(votes),
+		}
+	}
 }
 
 impl pallet_quadravote::Config for Test {
@@ -77,15 +162,65 @@ impl pallet_quadravote::Config for Test {
 	type Event = Event;
 	type IdentityProvider = VotingRegistry;
 	type MaxProposals = MaxProposals;
-	type PeriodLength = PeriodLength;
+	type ProposalPeriodLength = ProposalPeriodLength;
+	type VotingPeriodLength = VotingPeriodLength;
+	type CooldownPeriodLength = CooldownPeriodLength;
 	type MaxVotesPerAccount = MaxVotesPerAccount;
+	type VoteCostUnit = ConstU64<1>;
+	type VoteCostFunction = VoteCost;
+	type CostModel = CostModel;
 	type MaxVotersPerSession = MaxVotersPerSession;
+	type MaxRefundsPerBlock = MaxRefundsPerBlock;
+	type MaxTitleLength = MaxTitleLength;
+	type MaxDescriptionUriLength = MaxDescriptionUriLength;
+	type MaxCallLength = MaxCallLength;
+	type Proposal = Call;
+	type EnactmentOrigin = EnactmentOrigin;
+	type ProposalFilter = ProposalFilter;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxCallWeight = MaxCallWeight;
+	type EnactmentPeriod = EnactmentPeriod;
+	type MinimumTurnout = MinimumTurnout;
+	type MinimumVoters = MinimumVoters;
+	type Threshold = pallet_quadravote::SimpleMajority;
+	type Selection = Selection;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	// Proposals without metadata cost nothing, so the balances in most tests only
+	// reflect votes.
+	type MetadataDepositBase = ConstU64<0>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type ProposalDeposit = ProposalDeposit;
+	type DepositSlash = DepositSlash;
+	type Slash = ();
+	type PalletId = QuadravotePalletId;
+	type MaxContributors = MaxContributors;
+	type MaxTracks = ConstU32<4>;
+	type MaxTrackNameLength = ConstU32<16>;
+	type WeightInfo = ();
 }
 
 impl pallet_votingregistry::Config for Test {
 	type Event = Event;
 	type Currency = Balances;
 	type ReserveAmount = ConstU64<50>;
+	type VoiceCredits = ConstU32<100>;
+	type IdentityInUse = Quadravote;
+	type WeightInfo = ();
+}
+
+impl pallet_scheduler::Config for Test {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = ConstU64<2_000_000_000_000>;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = ConstU32<10>;
+	type WeightInfo = ();
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type PreimageProvider = ();
+	type NoPreimagePostponement = ();
 }
 
 impl pallet_balances::Config for Test {
@@ -102,9 +237,31 @@ impl pallet_balances::Config for Test {
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	// Tests that change the periods, quorum or selection may have run on this thread before.
+	ProposalPeriodLength::set(&PERIOD_LENGTH);
+	VotingPeriodLength::set(&PERIOD_LENGTH);
+	CooldownPeriodLength::set(&0);
+	MinimumTurnout::set(&1);
+	ProposalDeposit::set(&0);
+	MinimumVoters::set(&1);
+	Selection::set(&pallet_quadravote::SelectionMode::Majority);
+	UseVoiceCredits::set(&false);
+	LinearVoteCost::set(&false);
 	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
 }
 
+// Helper function to create a proposal without metadata.
+pub fn create_proposal(origin: Origin, proposal: [u8; 32]) -> DispatchResult {
+	Quadravote::create_proposal(
+		origin,
+		DEFAULT_TRACK,
+		proposal,
+		Default::default(),
+		Default::default(),
+		None,
+	)
+}
+
 // Helper function to fast forward to a specific block number.
 pub fn run_to_block(n: u64) {
 	while System::block_number() < n {
@@ -115,5 +272,7 @@ pub fn run_to_block(n: u64) {
 		System::set_block_number(System::block_number() + 1);
 		System::on_initialize(System::block_number());
 		Quadravote::on_initialize(System::block_number());
+		Scheduler::on_initialize(System::block_number());
+		Quadravote::do_try_state().unwrap();
 	}
 }
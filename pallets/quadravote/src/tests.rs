@@ -1,24 +1,38 @@
 use crate::{
+	migrations::{self, single_track},
 	mock::{run_to_block, *},
-	CountedProposals, Error,
+	ApprovalThreshold, CountedProposals, EnactedProposals, Error, FundingOf, Linear, MetadataOf,
+	Outcome, PendingEnactments, Phase, PositiveTurnoutBias, Power, Proposals, Quadratic,
+	SelectionMode, SessionVoters, SimpleMajority, SuperMajority, Tally, ThresholdRule, TrackInfo,
+	VoteCostFunction, VoteCostModel, VoteRecord, Voters, VotingProposal, DEFAULT_TRACK,
 };
-use frame_support::traits::Currency;
-use frame_support::{assert_noop, assert_ok, error::BadOrigin};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	error::BadOrigin,
+	storage::unhashed,
+	traits::{
+		schedule::{DispatchTime, Named as ScheduleNamed},
+		Currency, Get, GetStorageVersion, OnRuntimeUpgrade, ReservableCurrency, StorageVersion,
+	},
+	BoundedVec,
+};
+use sp_runtime::Perbill;
 
 #[test]
 fn call_create_proposal() {
 	new_test_ext().execute_with(|| {
-		let period_length = <Test as crate::Config>::PeriodLength::get();
+		let period_length = PERIOD_LENGTH;
 		let alice = 0u64;
 		// Set some balance
 		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
 
 		// Unsigned fails
-		assert_noop!(Quadravote::create_proposal(Origin::none(), [0u8; 32]), BadOrigin);
+		assert_noop!(create_proposal(Origin::none(), [0u8; 32]), BadOrigin);
 
 		// Signed fails while in the voting period
 		assert_noop!(
-			Quadravote::create_proposal(Origin::signed(alice), [0u8; 32]),
+			create_proposal(Origin::signed(alice), [0u8; 32]),
 			Error::<Test>::NotInProposalPeriod
 		);
 
@@ -27,7 +41,7 @@ fn call_create_proposal() {
 
 		// Signed does not work if not identified by the voting registry
 		assert_noop!(
-			Quadravote::create_proposal(Origin::signed(alice), [0u8; 32]),
+			create_proposal(Origin::signed(alice), [0u8; 32]),
 			Error::<Test>::NotIdentified
 		);
 
@@ -36,11 +50,11 @@ fn call_create_proposal() {
 		VotingRegistry::register(Origin::signed(alice)).unwrap();
 
 		// Signed works in the proposal period
-		assert_ok!(Quadravote::create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
 
 		// Submitting the same proposal fails
 		assert_noop!(
-			Quadravote::create_proposal(Origin::signed(alice), [0u8; 32]),
+			create_proposal(Origin::signed(alice), [0u8; 32]),
 			Error::<Test>::ProposalAlreadyExists
 		);
 	});
@@ -49,7 +63,7 @@ fn call_create_proposal() {
 #[test]
 fn call_create_proposal_maximum() {
 	new_test_ext().execute_with(|| {
-		let period_length = <Test as crate::Config>::PeriodLength::get();
+		let period_length = PERIOD_LENGTH;
 		let max_proposals = <Test as crate::Config>::MaxProposals::get();
 		let alice = 0u64;
 
@@ -63,10 +77,10 @@ fn call_create_proposal_maximum() {
 		// Advance the chain by the period length to get to proposal period
 		run_to_block(period_length.into());
 		for i in 0..max_proposals {
-			assert_ok!(Quadravote::create_proposal(Origin::signed(alice), [i as u8; 32]));
+			assert_ok!(create_proposal(Origin::signed(alice), [i as u8; 32]));
 		}
 		assert_noop!(
-			Quadravote::create_proposal(Origin::signed(alice), [10 as u8; 32]),
+			create_proposal(Origin::signed(alice), [10 as u8; 32]),
 			Error::<Test>::TooManyProposals
 		);
 	});
@@ -74,7 +88,7 @@ fn call_create_proposal_maximum() {
 #[test]
 fn call_withdraw_proposal() {
 	new_test_ext().execute_with(|| {
-		let period_length = <Test as crate::Config>::PeriodLength::get();
+		let period_length = PERIOD_LENGTH;
 		let alice = 0u64;
 		let evelyn = 1u64;
 
@@ -101,7 +115,7 @@ fn call_withdraw_proposal() {
 		run_to_block(period_length.into());
 
 		// Set up an existing proposal
-		Quadravote::create_proposal(Origin::signed(alice), [0u8; 32]).unwrap();
+		create_proposal(Origin::signed(alice), [0u8; 32]).unwrap();
 
 		// Withdrawing as another account id fails
 		assert_noop!(
@@ -120,10 +134,84 @@ fn call_withdraw_proposal() {
 	});
 }
 
+#[test]
+fn withdraw_proposal_keeps_storage_in_lockstep() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period
+		run_to_block(period_length.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [1u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [2u8; 32]));
+
+		// Withdraw the proposal in the middle
+		assert_ok!(Quadravote::withdraw_proposal(Origin::signed(alice), [1u8; 32]));
+		assert_ok!(Quadravote::do_try_state());
+		assert_eq!(CountedProposals::<Test>::count(), 2);
+		let hashes: Vec<[u8; 32]> = Proposals::<Test>::get(DEFAULT_TRACK)
+			.unwrap()
+			.iter()
+			.map(|p| p.proposal)
+			.collect();
+		assert_eq!(hashes, vec![[0u8; 32], [2u8; 32]]);
+
+		// A withdrawn proposal can be submitted again
+		assert_ok!(create_proposal(Origin::signed(alice), [1u8; 32]));
+		assert_ok!(Quadravote::withdraw_proposal(Origin::signed(alice), [1u8; 32]));
+		assert_ok!(Quadravote::do_try_state());
+	});
+}
+
+#[test]
+fn withdrawn_proposal_cannot_win() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let evelyn = 1u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		assert_ok!(Balances::set_balance(Origin::root(), evelyn, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+		VotingRegistry::register(Origin::signed(evelyn)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(evelyn), [1u8; 32]));
+		assert_ok!(Quadravote::withdraw_proposal(Origin::signed(alice), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// The withdrawn proposal can not be voted on
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0),
+			Error::<Test>::ProposalDoesNotExist
+		);
+		assert_ok!(Quadravote::cast_vote(Origin::signed(evelyn), [1u8; 32], 3, 0));
+
+		// Proposal period, then the enactment period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		run_to_block(System::block_number() + <Test as crate::Config>::EnactmentPeriod::get());
+
+		// Only the live proposal was enacted
+		assert!(EnactedProposals::<Test>::contains_key([1u8; 32]));
+		assert!(!EnactedProposals::<Test>::contains_key([0u8; 32]));
+	});
+}
+
 #[test]
 fn call_cast_vote() {
 	new_test_ext().execute_with(|| {
-		let period_length = <Test as crate::Config>::PeriodLength::get();
+		let period_length = PERIOD_LENGTH;
 		let alice = 0u64;
 		let evelyn = 1u64;
 
@@ -134,11 +222,11 @@ fn call_cast_vote() {
 		assert_ok!(Balances::set_balance(Origin::root(), evelyn, 10_000_000, 0));
 
 		// Unsigned call fails
-		assert_noop!(Quadravote::cast_vote(Origin::none(), 0, 0, 0), BadOrigin);
-		// Unidentified call fails
+		assert_noop!(Quadravote::cast_vote(Origin::none(), [0u8; 32], 0, 0), BadOrigin);
+		// Voting on a proposal that was never created fails whatever the phase
 		assert_noop!(
-			Quadravote::cast_vote(Origin::signed(alice), 0, 0, 0),
-			Error::<Test>::NotIdentified
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 0, 0),
+			Error::<Test>::ProposalDoesNotExist
 		);
 		// Register alice & evelyn with the votingregistry.
 		VotingRegistry::register(Origin::signed(alice)).unwrap();
@@ -149,39 +237,51 @@ fn call_cast_vote() {
 		let mut current_height = period_length;
 		run_to_block(current_height.into());
 		// Submit 2 proposals
-		assert_ok!(Quadravote::create_proposal(Origin::signed(alice), [0u8; 32]));
-		assert_ok!(Quadravote::create_proposal(Origin::signed(evelyn), [1u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(evelyn), [1u8; 32]));
 
 		// Voting period
 		// Advance the chain by the period length
 		current_height += period_length;
 		run_to_block(current_height.into());
 
+		// Voting on a proposal that does not exist fails
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [2u8; 32], 5, 0),
+			Error::<Test>::ProposalDoesNotExist
+		);
+
+		// Unidentified call fails
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(2), [0u8; 32], 5, 0),
+			Error::<Test>::NotIdentified
+		);
+
 		// Submit 5 votes for a proposal
 		// At this point alice is missing 50 from registering with the
 		// identity provider.
-		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), 0, 5, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0));
 		assert_eq!(Balances::free_balance(&alice), 9_999_925);
 
 		// Submit another 5 votes
-		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), 0, 5, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0));
 		assert_eq!(Balances::free_balance(&alice), 9_999_850);
 
 		// Submit another 5 votes
 		assert_noop!(
-			Quadravote::cast_vote(Origin::signed(alice), 0, 5, 0),
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0),
 			Error::<Test>::AllVotesCastForAccount
 		);
 
 		// Submit another 5 votes, this time exceeding the account voting limit
 		assert_noop!(
-			Quadravote::cast_vote(Origin::signed(alice), 0, 5, 0),
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0),
 			Error::<Test>::AllVotesCastForAccount
 		);
 
 		// Submit 5 votes to another proposal, should also affect the voting limit
 		assert_noop!(
-			Quadravote::cast_vote(Origin::signed(alice), 1, 5, 0),
+			Quadravote::cast_vote(Origin::signed(alice), [1u8; 32], 5, 0),
 			Error::<Test>::AllVotesCastForAccount
 		);
 
@@ -194,3 +294,1627 @@ fn call_cast_vote() {
 		assert_eq!(Balances::free_balance(&alice), 9_999_950);
 	});
 }
+
+#[test]
+fn call_cast_vote_on_multiple_proposals() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let evelyn = 1u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		assert_ok!(Balances::set_balance(Origin::root(), evelyn, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+		VotingRegistry::register(Origin::signed(evelyn)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [1u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// Votes are priced per proposal, 3 votes cost 9 and 4 votes cost 16
+		assert_ok!(Quadravote::cast_vote(Origin::signed(evelyn), [1u8; 32], 3, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(evelyn), [0u8; 32], 0, 4));
+		assert_eq!(Balances::free_balance(&evelyn), 9_999_925);
+
+		let proposals = crate::Proposals::<Test>::get(DEFAULT_TRACK).unwrap();
+		assert_eq!((proposals[0].votes_for, proposals[0].votes_against), (0, 4));
+		assert_eq!((proposals[1].votes_for, proposals[1].votes_against), (3, 0));
+
+		// Proposal period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// The reserve for both proposals is refunded
+		assert_eq!(Balances::free_balance(&evelyn), 9_999_950);
+	});
+}
+
+#[test]
+fn call_cast_vote_maximum_voters() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let max_voters = <Test as crate::Config>::MaxVotersPerSession::get() as u64;
+
+		for who in 0..=max_voters {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(0), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		for who in 0..max_voters {
+			assert_ok!(Quadravote::cast_vote(Origin::signed(who), [0u8; 32], 1, 0));
+		}
+
+		// One voter too many
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(max_voters), [0u8; 32], 1, 0),
+			Error::<Test>::TooManyVoters
+		);
+
+		// Existing voters can still add votes
+		assert_ok!(Quadravote::cast_vote(Origin::signed(0), [0u8; 32], 1, 0));
+	});
+}
+
+#[test]
+fn call_cast_vote_empty_first_vote() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+
+		assert_ok!(Balances::set_balance(Origin::root(), 1, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(1)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(1), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// Casting no votes does not take up a voter slot
+		assert_eq!(Quadravote::quote_vote(&1, &[0u8; 32], 0, 0), None);
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(1), [0u8; 32], 0, 0),
+			Error::<Test>::EmptyVote
+		);
+		assert_eq!(SessionVoters::<Test>::get(DEFAULT_TRACK), 0);
+
+		assert_ok!(Quadravote::cast_vote(Origin::signed(1), [0u8; 32], 1, 0));
+		assert_eq!(SessionVoters::<Test>::get(DEFAULT_TRACK), 1);
+	});
+}
+
+#[test]
+fn voters_are_refunded_over_multiple_blocks() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let refunds_per_block = <Test as crate::Config>::MaxRefundsPerBlock::get();
+		let voters = 5u64;
+
+		for who in 0..voters {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(0), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		for who in 0..voters {
+			assert_ok!(Quadravote::cast_vote(Origin::signed(who), [0u8; 32], 2, 0));
+			assert_eq!(Balances::free_balance(&who), 9_999_946);
+		}
+
+		// Proposal period, only `MaxRefundsPerBlock` voters are refunded per block
+		current_height += period_length;
+		let mut remaining = voters as u32;
+		while remaining > 0 {
+			run_to_block(current_height.into());
+			remaining = remaining.saturating_sub(refunds_per_block);
+			assert_eq!(Voters::<Test>::iter_prefix(DEFAULT_TRACK).count(), remaining);
+			current_height += 1;
+		}
+
+		for who in 0..voters {
+			assert_eq!(Balances::free_balance(&who), 9_999_950);
+		}
+	});
+}
+
+#[test]
+fn call_claim_refund() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let max_voters = <Test as crate::Config>::MaxVotersPerSession::get() as u64;
+
+		for who in 0..max_voters {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(0), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		for who in 0..max_voters {
+			assert_ok!(Quadravote::cast_vote(Origin::signed(who), [0u8; 32], 2, 0));
+		}
+
+		// Votes from the current round can not be refunded
+		assert_noop!(
+			Quadravote::claim_refund(Origin::signed(1), DEFAULT_TRACK, 0),
+			Error::<Test>::NoRefundDue
+		);
+
+		// Proposal period, the end of voting refunds the first voter
+		current_height += period_length;
+		run_to_block(current_height.into());
+		let stale_voter = Voters::<Test>::iter_key_prefix(DEFAULT_TRACK).next().unwrap();
+		assert_eq!(Balances::free_balance(&stale_voter), 9_999_946);
+
+		// Anyone can claim a refund on behalf of a voter
+		assert_noop!(
+			Quadravote::claim_refund(Origin::none(), DEFAULT_TRACK, stale_voter),
+			BadOrigin
+		);
+		assert_ok!(Quadravote::claim_refund(
+			Origin::signed(max_voters),
+			DEFAULT_TRACK,
+			stale_voter
+		));
+		assert_eq!(Balances::free_balance(&stale_voter), 9_999_950);
+		assert!(!Voters::<Test>::contains_key(DEFAULT_TRACK, stale_voter));
+
+		// Claiming twice fails
+		assert_noop!(
+			Quadravote::claim_refund(Origin::signed(stale_voter), DEFAULT_TRACK, stale_voter),
+			Error::<Test>::NoRefundDue
+		);
+	});
+}
+
+#[test]
+fn stale_voters_are_refunded_when_voting_again() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let max_voters = <Test as crate::Config>::MaxVotersPerSession::get() as u64;
+
+		for who in 0..max_voters {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(0), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		for who in 0..max_voters {
+			assert_ok!(Quadravote::cast_vote(Origin::signed(who), [0u8; 32], 2, 0));
+		}
+
+		// Proposal period, more voters than can be swept before voting starts again
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(0), [1u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert!(Voters::<Test>::iter_prefix(DEFAULT_TRACK).count() > 0);
+		let stale_voter = Voters::<Test>::iter_key_prefix(DEFAULT_TRACK).next().unwrap();
+		assert_eq!(Balances::free_balance(&stale_voter), 9_999_946);
+
+		// Voting again refunds the old reserve and starts from a clean slate
+		assert_ok!(Quadravote::cast_vote(Origin::signed(stale_voter), [1u8; 32], 1, 0));
+		assert_eq!(Balances::free_balance(&stale_voter), 9_999_949);
+		assert_eq!(crate::SessionVoters::<Test>::get(DEFAULT_TRACK), 1);
+
+		// The new round's voter can not claim a refund until the round is over
+		assert_noop!(
+			Quadravote::claim_refund(Origin::signed(stale_voter), DEFAULT_TRACK, stale_voter),
+			Error::<Test>::NoRefundDue
+		);
+	});
+}
+
+#[test]
+fn vote_cost_does_not_overflow() {
+	new_test_ext().execute_with(|| {
+		let unit = <Test as crate::Config>::VoteCostUnit::get();
+		assert_eq!(Quadravote::vote_cost(0).unwrap(), 0);
+		assert_eq!(Quadravote::vote_cost(10).unwrap(), 100 * unit);
+		// Squaring this many votes overflows a `u32`
+		assert_eq!(Quadravote::vote_cost(65_536).unwrap(), 4_294_967_296 * unit);
+		assert_eq!(Quadravote::vote_cost(u32::MAX).unwrap(), 18_446_744_065_119_617_025 * unit);
+	});
+}
+
+#[test]
+fn call_adjust_vote() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let evelyn = 1u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		assert_ok!(Balances::set_balance(Origin::root(), evelyn, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+		VotingRegistry::register(Origin::signed(evelyn)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+
+		// Adjusting outside of the voting period fails
+		assert_noop!(
+			Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 0, 0),
+			Error::<Test>::NotInVotingPeriod
+		);
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// Unsigned fails
+		assert_noop!(Quadravote::adjust_vote(Origin::none(), [0u8; 32], 0, 0), BadOrigin);
+
+		// Adjusting without having voted fails
+		assert_noop!(
+			Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 0, 0),
+			Error::<Test>::NoVotesToAdjust
+		);
+
+		// 6 for and 2 against costs 64
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 6, 2));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(evelyn), [0u8; 32], 0, 3));
+		assert_eq!(Balances::free_balance(&alice), 9_999_886);
+
+		// Votes can not be raised or moved to the other side
+		assert_noop!(
+			Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 7, 0),
+			Error::<Test>::CanOnlyLowerVotes
+		);
+		assert_noop!(
+			Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 0, 3),
+			Error::<Test>::CanOnlyLowerVotes
+		);
+
+		// Lowering to 3 for and 0 against costs 9, so 55 is released
+		assert_ok!(Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 3, 0));
+		assert_eq!(Balances::free_balance(&alice), 9_999_941);
+		let proposal = &Proposals::<Test>::get(DEFAULT_TRACK).unwrap()[0];
+		assert_eq!((proposal.votes_for, proposal.votes_against), (3, 3));
+
+		// The released votes can be cast again
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 7, 0));
+		assert_eq!(Balances::free_balance(&alice), 9_999_850);
+
+		// Retracting all votes releases the whole reserve
+		assert_ok!(Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 0, 0));
+		assert_eq!(Balances::free_balance(&alice), 9_999_950);
+		let proposal = &Proposals::<Test>::get(DEFAULT_TRACK).unwrap()[0];
+		assert_eq!((proposal.votes_for, proposal.votes_against), (0, 3));
+
+		// Proposal period, the retracted proposal did not win
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert!(!EnactedProposals::<Test>::contains_key([0u8; 32]));
+		assert_eq!(Balances::free_balance(&alice), 9_999_950);
+	});
+}
+
+#[test]
+fn votes_are_counted_per_direction() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [1u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert!(Quadravote::votes_of(&alice).is_empty());
+
+		// 5 for costs 25
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0));
+		assert_eq!(Quadravote::reserve_of(&alice), 25);
+
+		// 2 against are kept apart from the votes for, 7 votes cost 49
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 0, 2));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [1u8; 32], 1, 0));
+		assert_eq!(
+			Quadravote::votes_of(&alice),
+			vec![
+				([0u8; 32], VoteRecord { aye: 5, nay: 2 }),
+				([1u8; 32], VoteRecord { aye: 1, nay: 0 })
+			]
+		);
+		assert_eq!(Quadravote::reserve_of(&alice), 50);
+		assert_eq!(Balances::free_balance(&alice), 9_999_900);
+		let proposals = Proposals::<Test>::get(DEFAULT_TRACK).unwrap();
+		assert_eq!((proposals[0].votes_for, proposals[0].votes_against), (5, 2));
+		assert_eq!((proposals[1].votes_for, proposals[1].votes_against), (1, 0));
+
+		// Reversing the vote takes back the votes for, 2 against cost 4
+		assert_ok!(Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 0, 2));
+		assert_eq!(Quadravote::votes_of(&alice)[0], ([0u8; 32], VoteRecord { aye: 0, nay: 2 }));
+		assert_eq!(Quadravote::reserve_of(&alice), 5);
+		let proposals = Proposals::<Test>::get(DEFAULT_TRACK).unwrap();
+		assert_eq!((proposals[0].votes_for, proposals[0].votes_against), (0, 2));
+
+		// Votes of an earlier round are not reported
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert!(Quadravote::votes_of(&alice).is_empty());
+	});
+}
+
+#[test]
+fn migrate_voters_to_v1() {
+	new_test_ext().execute_with(|| {
+		let alice = 0u64;
+		StorageVersion::new(0).put::<Quadravote>();
+
+		// A voter in the old layout with 3 and 4 votes on two proposals, 25 reserved
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		assert_ok!(Balances::reserve(&alice, 25));
+		unhashed::put(
+			&single_track::Voters::<Test>::hashed_key_for(alice),
+			&(7u32, 25u64, vec![3u32, 4u32]),
+		);
+		single_track::Proposals::<Test>::put(BoundedVec::truncate_from(vec![VotingProposal {
+			proposal: [0u8; 32],
+			votes_for: 3,
+			votes_against: 4,
+		}]));
+
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Quadravote::on_chain_storage_version(), 1);
+		assert_eq!(Balances::free_balance(&alice), 10_000_000);
+		assert!(!single_track::Voters::<Test>::contains_key(alice));
+		let proposal = &single_track::Proposals::<Test>::get().unwrap()[0];
+		assert_eq!((proposal.votes_for, proposal.votes_against), (0, 0));
+
+		// Running the migration again does nothing
+		assert_ok!(Balances::reserve(&alice, 25));
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Balances::free_balance(&alice), 9_999_975);
+	});
+}
+
+#[test]
+fn query_phase_and_quotes() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Proposal);
+		assert_eq!(Quadravote::blocks_remaining(DEFAULT_TRACK, current_height.into()), 5);
+		assert_eq!(Quadravote::blocks_remaining(DEFAULT_TRACK, (current_height + 4).into()), 1);
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_eq!(
+			Quadravote::tallies(DEFAULT_TRACK),
+			vec![VotingProposal { proposal: [0u8; 32], votes_for: 0, votes_against: 0 }]
+		);
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+
+		// The first 3 votes cost 9, the next 2 another 16
+		assert_eq!(Quadravote::quote_vote(&alice, &[0u8; 32], 3, 0), Some(9));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 3, 0));
+		assert_eq!(Quadravote::quote_vote(&alice, &[0u8; 32], 2, 0), Some(16));
+
+		// Lowering the position is free, going over the maximum is rejected
+		assert_eq!(Quadravote::quote_vote(&alice, &[0u8; 32], 0, 1), Some(0));
+		assert_eq!(Quadravote::quote_vote(&alice, &[0u8; 32], 8, 0), None);
+		assert_eq!(
+			Quadravote::tallies(DEFAULT_TRACK),
+			vec![VotingProposal { proposal: [0u8; 32], votes_for: 3, votes_against: 0 }]
+		);
+	});
+}
+
+#[test]
+fn proposal_metadata_deposit() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		let title: BoundedVec<u8, MaxTitleLength> = b"Bridge".to_vec().try_into().unwrap();
+		let uri: BoundedVec<u8, MaxDescriptionUriLength> =
+			b"ipfs://bridge".to_vec().try_into().unwrap();
+		let call: BoundedVec<u8, MaxCallLength> = vec![1u8, 2, 3].try_into().unwrap();
+		let call_hash = sp_io::hashing::blake2_256(&call);
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+
+		// A call can only be attached to the proposal that is its hash
+		assert_noop!(
+			Quadravote::create_proposal(
+				Origin::signed(alice),
+				DEFAULT_TRACK,
+				[0u8; 32],
+				title.clone(),
+				uri.clone(),
+				Some(call.clone())
+			),
+			Error::<Test>::PreimageMismatch
+		);
+
+		// 6 + 13 + 3 bytes are reserved at 1 per byte
+		assert_ok!(Quadravote::create_proposal(
+			Origin::signed(alice),
+			DEFAULT_TRACK,
+			call_hash,
+			title.clone(),
+			uri.clone(),
+			Some(call.clone())
+		));
+		assert_eq!(Balances::free_balance(&alice), 9_999_928);
+		let metadata = MetadataOf::<Test>::get(call_hash).unwrap();
+		assert_eq!(metadata.title, title);
+		assert_eq!(metadata.description_uri, uri);
+		assert_eq!(metadata.call, Some(call));
+		assert_eq!(metadata.deposit, 22);
+
+		// Withdrawing returns the deposit
+		assert_ok!(Quadravote::withdraw_proposal(Origin::signed(alice), call_hash));
+		assert_eq!(Balances::free_balance(&alice), 9_999_950);
+		assert!(!MetadataOf::<Test>::contains_key(call_hash));
+
+		// The end of the voting period returns the deposit
+		assert_ok!(Quadravote::create_proposal(
+			Origin::signed(alice),
+			DEFAULT_TRACK,
+			[0u8; 32],
+			title,
+			uri,
+			None
+		));
+		assert_eq!(Balances::free_balance(&alice), 9_999_931);
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert!(MetadataOf::<Test>::contains_key([0u8; 32]));
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert!(!MetadataOf::<Test>::contains_key([0u8; 32]));
+		assert_eq!(Balances::free_balance(&alice), 9_999_950);
+	});
+}
+
+#[test]
+fn winning_proposal_call_is_enacted() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let bob = 5u64;
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// A call that needs root and one that can not be dispatched by root
+		let set_balance: BoundedVec<u8, MaxCallLength> = BoundedVec::truncate_from(
+			Call::Balances(pallet_balances::Call::set_balance {
+				who: bob,
+				new_free: 1_000,
+				new_reserved: 0,
+			})
+			.encode(),
+		);
+		let set_balance_hash = sp_io::hashing::blake2_256(&set_balance);
+		let remark: BoundedVec<u8, MaxCallLength> = BoundedVec::truncate_from(
+			Call::System(frame_system::Call::remark_with_event { remark: vec![1] }).encode(),
+		);
+		let remark_hash = sp_io::hashing::blake2_256(&remark);
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+
+		// Calls that do not decode are rejected
+		let garbage: BoundedVec<u8, MaxCallLength> = BoundedVec::truncate_from(vec![255u8; 4]);
+		assert_noop!(
+			Quadravote::create_proposal(
+				Origin::signed(alice),
+				DEFAULT_TRACK,
+				sp_io::hashing::blake2_256(&garbage),
+				Default::default(),
+				Default::default(),
+				Some(garbage)
+			),
+			Error::<Test>::UndecodableCall
+		);
+
+		// So are calls the proposal filter does not let through
+		let set_code: BoundedVec<u8, MaxCallLength> = BoundedVec::truncate_from(
+			Call::System(frame_system::Call::set_code { code: vec![1] }).encode(),
+		);
+		assert_noop!(
+			Quadravote::create_proposal(
+				Origin::signed(alice),
+				DEFAULT_TRACK,
+				sp_io::hashing::blake2_256(&set_code),
+				Default::default(),
+				Default::default(),
+				Some(set_code)
+			),
+			Error::<Test>::CallFiltered
+		);
+
+		assert_ok!(Quadravote::create_proposal(
+			Origin::signed(alice),
+			DEFAULT_TRACK,
+			set_balance_hash,
+			Default::default(),
+			Default::default(),
+			Some(set_balance)
+		));
+		assert_ok!(Quadravote::create_proposal(
+			Origin::signed(alice),
+			DEFAULT_TRACK,
+			remark_hash,
+			Default::default(),
+			Default::default(),
+			Some(remark)
+		));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), set_balance_hash, 1, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), remark_hash, 1, 0));
+		assert_eq!(Balances::free_balance(&bob), 0);
+
+		// Proposal period, both proposals won and are scheduled for enactment
+		current_height += period_length;
+		run_to_block(current_height.into());
+		let when = System::block_number() + <Test as crate::Config>::EnactmentPeriod::get();
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalScheduled {
+			proposal: set_balance_hash,
+			when,
+		}));
+		assert!(!EnactedProposals::<Test>::contains_key(set_balance_hash));
+		assert_eq!(Balances::free_balance(&bob), 0);
+
+		// Only root can enact, and only pending proposals
+		assert_noop!(Quadravote::enact(Origin::signed(alice), set_balance_hash), BadOrigin);
+		assert_noop!(
+			Quadravote::enact(Origin::root(), [0u8; 32]),
+			Error::<Test>::ProposalNotPending
+		);
+
+		// The enactment period is over and the calls were dispatched
+		run_to_block(when);
+		assert!(EnactedProposals::<Test>::contains_key(set_balance_hash));
+		assert!(!PendingEnactments::<Test>::contains_key(set_balance_hash));
+		assert_eq!(Balances::free_balance(&bob), 1_000);
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalEnacted {
+			proposal: set_balance_hash,
+			result: Ok(()),
+		}));
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalEnacted {
+			proposal: remark_hash,
+			result: Err(BadOrigin.into()),
+		}));
+	});
+}
+
+#[test]
+fn unschedulable_proposal_is_left_pending() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Take the scheduler name the enactment of the proposal would use
+		assert_ok!(<Scheduler as ScheduleNamed<u64, Call, OriginCaller>>::schedule_named(
+			(*b"quadvote", [0u8; 32]).encode(),
+			DispatchTime::At(1_000),
+			None,
+			63,
+			frame_system::RawOrigin::Root.into(),
+			Call::System(frame_system::Call::remark { remark: vec![] }),
+		));
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 1, 0));
+
+		// The proposal won but could not be scheduled, it is not enacted in its place
+		current_height += period_length;
+		run_to_block(current_height.into());
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalNotScheduled {
+			proposal: [0u8; 32],
+		}));
+		run_to_block(System::block_number() + <Test as crate::Config>::EnactmentPeriod::get());
+		assert!(PendingEnactments::<Test>::contains_key([0u8; 32]));
+		assert!(!EnactedProposals::<Test>::contains_key([0u8; 32]));
+
+		// Root can still enact it
+		assert_ok!(Quadravote::enact(Origin::root(), [0u8; 32]));
+		assert!(EnactedProposals::<Test>::contains_key([0u8; 32]));
+	});
+}
+
+#[test]
+fn winning_repeal_removes_enacted_proposal() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let target = [1u8; 32];
+		let repeal = Quadravote::repeal_hash(&target);
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+
+		// Only enacted proposals can be repealed
+		assert_noop!(
+			Quadravote::propose_repeal(
+				Origin::signed(alice),
+				DEFAULT_TRACK,
+				target,
+				Default::default(),
+				Default::default()
+			),
+			Error::<Test>::NotEnacted
+		);
+		EnactedProposals::<Test>::insert(target, ());
+		assert_ok!(Quadravote::propose_repeal(
+			Origin::signed(alice),
+			DEFAULT_TRACK,
+			target,
+			Default::default(),
+			Default::default()
+		));
+		assert_eq!(MetadataOf::<Test>::get(repeal).unwrap().repeals, Some(target));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), repeal, 1, 0));
+
+		// Proposal period, the repeal won and is pending
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_eq!(PendingEnactments::<Test>::get(repeal).unwrap().repeals, Some(target));
+		assert!(EnactedProposals::<Test>::contains_key(target));
+
+		// Once enacted, the target is gone and the repeal itself is not recorded
+		run_to_block(System::block_number() + <Test as crate::Config>::EnactmentPeriod::get());
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalRepealed {
+			proposal: repeal,
+			target,
+		}));
+		assert!(!EnactedProposals::<Test>::contains_key(target));
+		assert!(!EnactedProposals::<Test>::contains_key(repeal));
+		assert!(!PendingEnactments::<Test>::contains_key(repeal));
+	});
+}
+
+#[test]
+fn proposals_need_quorum_and_threshold_to_win() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let bob = 1u64;
+		MinimumTurnout::set(&3);
+		MinimumVoters::set(&2);
+
+		for who in [alice, bob] {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		for proposal in [[0u8; 32], [1u8; 32], [2u8; 32], [3u8; 32]] {
+			assert_ok!(create_proposal(Origin::signed(alice), proposal));
+		}
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		// Reaches the quorum and a majority
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 2, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), [0u8; 32], 1, 0));
+		// Enough votes, but from a single voter
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [1u8; 32], 3, 0));
+		// Two voters, but too few votes once one retracts
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [2u8; 32], 1, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), [2u8; 32], 1, 0));
+		assert_ok!(Quadravote::adjust_vote(Origin::signed(bob), [2u8; 32], 0, 0));
+		assert_eq!(crate::ProposalVoters::<Test>::get([2u8; 32]), 1);
+		// Reaches the quorum, but no majority
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [3u8; 32], 2, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), [3u8; 32], 0, 2));
+
+		// Proposal period, only the first proposal won
+		current_height += period_length;
+		run_to_block(current_height.into());
+		for (proposal, votes_for, votes_against, voters, outcome) in [
+			([0u8; 32], 3, 0, 2, Outcome::Approved),
+			([1u8; 32], 3, 0, 1, Outcome::TooFewVoters),
+			([2u8; 32], 1, 0, 1, Outcome::TurnoutTooLow),
+			([3u8; 32], 2, 2, 2, Outcome::ThresholdNotMet),
+		] {
+			System::assert_has_event(Event::Quadravote(crate::Event::ProposalTallied {
+				proposal,
+				tally: Tally { votes_for, votes_against, voters },
+				outcome,
+			}));
+		}
+		assert!(PendingEnactments::<Test>::contains_key([0u8; 32]));
+		assert!(!PendingEnactments::<Test>::contains_key([1u8; 32]));
+		assert!(!PendingEnactments::<Test>::contains_key([2u8; 32]));
+		assert!(!PendingEnactments::<Test>::contains_key([3u8; 32]));
+		assert_eq!(crate::ProposalVoters::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn approval_thresholds() {
+	frame_support::parameter_types! {
+		pub const TwoThirds: Perbill = Perbill::from_rational(2u32, 3u32);
+	}
+	let tally = |votes_for, votes_against| Tally { votes_for, votes_against, voters: 2 };
+
+	assert!(SimpleMajority::approved(&tally(2, 1), 0));
+	assert!(!SimpleMajority::approved(&tally(1, 1), 0));
+
+	assert!(SuperMajority::<TwoThirds>::approved(&tally(7, 3), 0));
+	assert!(!SuperMajority::<TwoThirds>::approved(&tally(6, 3), 0));
+
+	// A low turnout needs a super-majority, a full turnout a simple majority
+	assert!(!PositiveTurnoutBias::approved(&tally(6, 4), 100));
+	assert!(PositiveTurnoutBias::approved(&tally(9, 1), 100));
+	assert!(PositiveTurnoutBias::approved(&tally(51, 49), 100));
+	assert!(!PositiveTurnoutBias::approved(&tally(50, 50), 100));
+}
+
+#[test]
+fn top_k_selection_picks_highest_scores() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let bob = 1u64;
+		Selection::set(&SelectionMode::TopK(2));
+
+		for who in [alice, bob] {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		let proposals = [[0u8; 32], [1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]];
+		for proposal in proposals {
+			assert_ok!(create_proposal(Origin::signed(alice), proposal));
+		}
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), proposals[0], 3, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), proposals[1], 4, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), proposals[1], 0, 1));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), proposals[2], 3, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), proposals[3], 1, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), proposals[3], 0, 2));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), proposals[4], 1, 0));
+
+		// Proposal period. The first three proposals share the highest score: the second has
+		// the most votes for and the first was created before the third.
+		current_height += period_length;
+		run_to_block(current_height.into());
+		System::assert_has_event(Event::Quadravote(crate::Event::WinningProposals {
+			winners: BoundedVec::truncate_from(vec![
+				VotingProposal { proposal: proposals[1], votes_for: 4, votes_against: 1 },
+				VotingProposal { proposal: proposals[0], votes_for: 3, votes_against: 0 },
+			]),
+		}));
+		for (proposal, outcome) in [
+			(proposals[2], Outcome::OutRanked),
+			(proposals[3], Outcome::ThresholdNotMet),
+			(proposals[4], Outcome::OutRanked),
+		] {
+			assert!(System::events().iter().any(|record| matches!(
+				record.event,
+				Event::Quadravote(crate::Event::ProposalTallied { proposal: p, outcome: o, .. })
+					if p == proposal && o == outcome
+			)));
+		}
+		assert!(PendingEnactments::<Test>::contains_key(proposals[0]));
+		assert!(PendingEnactments::<Test>::contains_key(proposals[1]));
+		assert!(!PendingEnactments::<Test>::contains_key(proposals[2]));
+	});
+}
+
+#[test]
+fn matching_pool_is_shared_by_quadratic_funding() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let (alice, bob, charlie) = (0u64, 1u64, 2u64);
+		let (first_beneficiary, second_beneficiary) = (10u64, 11u64);
+		let pool = Quadravote::matching_pool_account(DEFAULT_TRACK);
+		let other_pool = Quadravote::matching_pool_account(1);
+		assert_ne!(pool, other_pool);
+
+		for who in [alice, bob, charlie] {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+		// 1_000 to share, the rest keeps the pool alive
+		assert_ok!(Balances::set_balance(Origin::root(), pool, 1_001, 0));
+		assert_ok!(Balances::set_balance(Origin::root(), other_pool, 500, 0));
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		for (proposal, beneficiary) in
+			[([0u8; 32], first_beneficiary), ([1u8; 32], second_beneficiary)]
+		{
+			assert_ok!(Quadravote::create_funding_proposal(
+				Origin::signed(alice),
+				DEFAULT_TRACK,
+				proposal,
+				Default::default(),
+				Default::default(),
+				beneficiary
+			));
+		}
+		assert_ok!(create_proposal(Origin::signed(alice), [2u8; 32]));
+		assert_noop!(
+			Quadravote::contribute(Origin::signed(alice), [0u8; 32], 100),
+			Error::<Test>::NotInVotingPeriod
+		);
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_noop!(
+			Quadravote::contribute(Origin::signed(alice), [2u8; 32], 100),
+			Error::<Test>::NotAFundingProposal
+		);
+		assert_noop!(
+			Quadravote::contribute(Origin::signed(alice), [0u8; 32], 0),
+			Error::<Test>::ZeroContribution
+		);
+		assert_noop!(
+			Quadravote::contribute(Origin::signed(3), [0u8; 32], 100),
+			Error::<Test>::NotIdentified
+		);
+
+		// Contributions are transferred straight away. Two contributions of 100 match
+		// (10 + 10)² - 200 = 200, contributions of 400 and 225 match (20 + 15)² - 625 = 600.
+		assert_ok!(Quadravote::contribute(Origin::signed(alice), [0u8; 32], 40));
+		assert_ok!(Quadravote::contribute(Origin::signed(alice), [0u8; 32], 60));
+		assert_ok!(Quadravote::contribute(Origin::signed(bob), [0u8; 32], 100));
+		assert_ok!(Quadravote::contribute(Origin::signed(charlie), [1u8; 32], 400));
+		assert_ok!(Quadravote::contribute(Origin::signed(alice), [1u8; 32], 225));
+		assert_eq!(Balances::free_balance(&first_beneficiary), 200);
+		assert_eq!(Balances::free_balance(&second_beneficiary), 625);
+
+		// Proposal period, the pool is shared a quarter to three quarters
+		current_height += period_length;
+		run_to_block(current_height.into());
+		System::assert_has_event(Event::Quadravote(crate::Event::MatchingDistributed {
+			proposal: [0u8; 32],
+			beneficiary: first_beneficiary,
+			amount: 250,
+		}));
+		System::assert_has_event(Event::Quadravote(crate::Event::MatchingDistributed {
+			proposal: [1u8; 32],
+			beneficiary: second_beneficiary,
+			amount: 750,
+		}));
+		assert_eq!(Balances::free_balance(&first_beneficiary), 450);
+		assert_eq!(Balances::free_balance(&second_beneficiary), 1_375);
+		assert_eq!(Balances::free_balance(&pool), 1);
+		assert_eq!(FundingOf::<Test>::iter().count(), 0);
+
+		// The pool of another track is left alone
+		assert_eq!(Balances::free_balance(&other_pool), 500);
+	});
+}
+
+#[test]
+fn periods_have_their_own_lengths() {
+	new_test_ext().execute_with(|| {
+		let alice = 0u64;
+		ProposalPeriodLength::set(&2);
+		VotingPeriodLength::set(&6);
+		CooldownPeriodLength::set(&3);
+
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// The chain starts in a voting period
+		run_to_block(5);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+
+		// Proposal period
+		run_to_block(6);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Proposal);
+		assert_eq!(crate::PhaseStart::<Test>::get(DEFAULT_TRACK), 6);
+		assert_eq!(Quadravote::blocks_remaining(DEFAULT_TRACK, 6), 2);
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+
+		// Voting period
+		run_to_block(8);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+		assert_eq!(Quadravote::blocks_remaining(DEFAULT_TRACK, 8), 6);
+		assert_noop!(
+			create_proposal(Origin::signed(alice), [1u8; 32]),
+			Error::<Test>::NotInProposalPeriod
+		);
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 1, 0));
+
+		// Cool-down, neither proposals nor votes are accepted
+		run_to_block(14);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Cooldown);
+		assert_eq!(Quadravote::blocks_remaining(DEFAULT_TRACK, 14), 3);
+		assert_noop!(
+			create_proposal(Origin::signed(alice), [1u8; 32]),
+			Error::<Test>::NotInProposalPeriod
+		);
+		// The proposals of the round are gone
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 1, 0),
+			Error::<Test>::ProposalDoesNotExist
+		);
+
+		// Proposal period
+		run_to_block(17);
+		System::assert_has_event(Event::Quadravote(crate::Event::CooldownPeriodEnded {
+			track: DEFAULT_TRACK,
+			block: 17,
+		}));
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Proposal);
+		assert_ok!(create_proposal(Origin::signed(alice), [1u8; 32]));
+	});
+}
+
+#[test]
+fn migrate_phase_start_to_v2() {
+	new_test_ext().execute_with(|| {
+		frame_support::parameter_types! {
+			pub const OldPeriodLength: u32 = 5;
+		}
+		StorageVersion::new(1).put::<Quadravote>();
+		System::set_block_number(13);
+
+		migrations::v2::MigrateToV2::<Test, OldPeriodLength>::on_runtime_upgrade();
+		assert_eq!(single_track::PhaseStart::<Test>::get(), 10);
+		assert_eq!(Quadravote::on_chain_storage_version(), 2);
+
+		// Only runs once
+		System::set_block_number(18);
+		migrations::v2::MigrateToV2::<Test, OldPeriodLength>::on_runtime_upgrade();
+		assert_eq!(single_track::PhaseStart::<Test>::get(), 10);
+	});
+}
+
+#[test]
+fn migrate_phase_start_to_v2_at_period_boundary() {
+	new_test_ext().execute_with(|| {
+		frame_support::parameter_types! {
+			pub const OldPeriodLength: u32 = 5;
+		}
+		StorageVersion::new(1).put::<Quadravote>();
+		System::set_block_number(15);
+
+		// The phase change of block 15 has not happened yet, the phase started in block 10
+		migrations::v2::MigrateToV2::<Test, OldPeriodLength>::on_runtime_upgrade();
+		assert_eq!(single_track::PhaseStart::<Test>::get(), 10);
+		assert_eq!(Quadravote::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn migrate_phase_start_to_v2_without_period() {
+	new_test_ext().execute_with(|| {
+		frame_support::parameter_types! {
+			pub const OldPeriodLength: u32 = 0;
+		}
+		StorageVersion::new(1).put::<Quadravote>();
+		System::set_block_number(13);
+
+		// A zero period length does not divide by zero, the phase starts now
+		migrations::v2::MigrateToV2::<Test, OldPeriodLength>::on_runtime_upgrade();
+		assert_eq!(single_track::PhaseStart::<Test>::get(), 13);
+		assert_eq!(Quadravote::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn migrate_single_cycle_to_v3() {
+	new_test_ext().execute_with(|| {
+		let alice = 0u64;
+		StorageVersion::new(2).put::<Quadravote>();
+
+		// A cycle in the voting period of its second round, with one proposal and a voter
+		// with 2 votes for it, 4 reserved
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		assert_ok!(Balances::reserve(&alice, 4));
+		unhashed::put(
+			&single_track::Voters::<Test>::hashed_key_for(alice),
+			&(1u32, 2u32, 4u64, vec![([0u8; 32], VoteRecord { aye: 2, nay: 0 })]),
+		);
+		single_track::CounterForVoters::<Test>::put(1);
+		// and a withdrawn proposal that was left behind
+		single_track::Proposals::<Test>::put(BoundedVec::truncate_from(vec![
+			VotingProposal { proposal: [0u8; 32], votes_for: 2, votes_against: 0 },
+			VotingProposal { proposal: [1u8; 32], votes_for: 0, votes_against: 0 },
+		]));
+		CountedProposals::<Test>::insert([0u8; 32], alice);
+		crate::ProposalVoters::<Test>::insert([0u8; 32], 1);
+		single_track::PhaseStart::<Test>::put(10);
+		single_track::VotingRound::<Test>::put(1);
+		single_track::SessionVoters::<Test>::put(1);
+
+		migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Quadravote::on_chain_storage_version(), 3);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+		assert_eq!(crate::PhaseStart::<Test>::get(DEFAULT_TRACK), 10);
+		assert_eq!(crate::VotingRound::<Test>::get(DEFAULT_TRACK), 1);
+		assert_eq!(crate::SessionVoters::<Test>::get(DEFAULT_TRACK), 1);
+		assert_eq!(crate::TrackOf::<Test>::get([0u8; 32]), DEFAULT_TRACK);
+		assert_eq!(Proposals::<Test>::get(DEFAULT_TRACK).unwrap()[0].votes_for, 2);
+		assert_eq!(Proposals::<Test>::get(DEFAULT_TRACK).unwrap().len(), 1);
+		assert!(!crate::TrackOf::<Test>::contains_key([1u8; 32]));
+		assert_eq!(Quadravote::reserve_of(&alice), 4);
+		assert_eq!(Quadravote::votes_of(&alice), vec![([0u8; 32], VoteRecord { aye: 2, nay: 0 })]);
+		assert!(crate::VotersOf::<Test>::contains_key([0u8; 32], alice));
+		assert!(!single_track::CounterForVoters::<Test>::exists());
+		assert_ok!(Quadravote::do_try_state());
+
+		// Only runs once
+		single_track::PhaseStart::<Test>::put(12);
+		migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+		assert_eq!(crate::PhaseStart::<Test>::get(DEFAULT_TRACK), 10);
+	});
+}
+
+fn track_info(proposal_period_length: u32, voting_period_length: u32) -> TrackInfo<Test> {
+	TrackInfo {
+		name: BoundedVec::truncate_from(b"treasury".to_vec()),
+		proposal_period_length,
+		voting_period_length,
+		cooldown_period_length: 0,
+		max_proposals: 2,
+		max_votes_per_account: 10,
+		minimum_turnout: 1,
+		minimum_voters: 1,
+		threshold: Some(ThresholdRule::SuperMajority(Perbill::from_percent(66))),
+		selection: SelectionMode::Majority,
+	}
+}
+
+#[test]
+fn tracks_run_their_own_cycles() {
+	new_test_ext().execute_with(|| {
+		let alice = 0u64;
+		let treasury = 1;
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// The default track is in its proposal period from block 5 to 10
+		run_to_block(5);
+
+		// Only the admin registers valid tracks
+		assert_noop!(
+			Quadravote::register_track(Origin::signed(alice), treasury, track_info(3, 4)),
+			BadOrigin
+		);
+		assert_noop!(
+			Quadravote::register_track(Origin::root(), treasury, track_info(0, 4)),
+			Error::<Test>::InvalidTrack
+		);
+		assert_ok!(Quadravote::register_track(Origin::root(), treasury, track_info(3, 4)));
+		System::assert_has_event(Event::Quadravote(crate::Event::TrackRegistered {
+			track: treasury,
+		}));
+		assert_eq!(Quadravote::phase(treasury), Phase::Proposal);
+		assert_eq!(
+			Quadravote::tracks(),
+			vec![(DEFAULT_TRACK, Vec::new()), (treasury, b"treasury".to_vec())]
+		);
+		assert_noop!(
+			Quadravote::create_proposal(
+				Origin::signed(alice),
+				7,
+				[0u8; 32],
+				Default::default(),
+				Default::default(),
+				None
+			),
+			Error::<Test>::UnknownTrack
+		);
+
+		// The track limits its own proposals
+		for proposal in [[1u8; 32], [2u8; 32]] {
+			assert_ok!(Quadravote::create_proposal(
+				Origin::signed(alice),
+				treasury,
+				proposal,
+				Default::default(),
+				Default::default(),
+				None
+			));
+		}
+		assert_noop!(
+			Quadravote::create_proposal(
+				Origin::signed(alice),
+				treasury,
+				[3u8; 32],
+				Default::default(),
+				Default::default(),
+				None
+			),
+			Error::<Test>::TooManyProposals
+		);
+		assert_ok!(create_proposal(Origin::signed(alice), [3u8; 32]));
+
+		// The track votes after 3 blocks while the default track keeps taking proposals
+		run_to_block(8);
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalPeriodEnded {
+			track: treasury,
+			block: 8,
+		}));
+		assert_eq!(Quadravote::phase(treasury), Phase::Voting);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Proposal);
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [1u8; 32], 2, 0));
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [3u8; 32], 1, 0),
+			Error::<Test>::NotInVotingPeriod
+		);
+		assert!(Voters::<Test>::contains_key(treasury, alice));
+		assert!(!Voters::<Test>::contains_key(DEFAULT_TRACK, alice));
+
+		// A track in use can not be removed
+		assert_noop!(Quadravote::remove_track(Origin::root(), treasury), Error::<Test>::TrackInUse);
+
+		// The voting period of the track ends, its proposals are closed and its voters
+		// refunded
+		run_to_block(12);
+		System::assert_has_event(Event::Quadravote(crate::Event::VotingPeriodEnded {
+			track: treasury,
+			block: 12,
+		}));
+		assert_eq!(Quadravote::phase(treasury), Phase::Proposal);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+		assert_eq!(Quadravote::tallies(treasury), vec![]);
+		assert_eq!(Quadravote::tallies(DEFAULT_TRACK).len(), 1);
+		assert!(!Voters::<Test>::contains_key(treasury, alice));
+
+		assert_ok!(Quadravote::remove_track(Origin::root(), treasury));
+		System::assert_has_event(Event::Quadravote(crate::Event::TrackRemoved { track: treasury }));
+		assert_eq!(Quadravote::track_ids(), vec![DEFAULT_TRACK]);
+		assert_noop!(
+			Quadravote::remove_track(Origin::root(), treasury),
+			Error::<Test>::UnknownTrack
+		);
+	});
+}
+
+#[test]
+fn tracks_are_limited() {
+	new_test_ext().execute_with(|| {
+		let max_tracks: u32 = <Test as crate::Config>::MaxTracks::get();
+		for track in 1..=max_tracks {
+			assert_ok!(Quadravote::register_track(Origin::root(), track as u16, track_info(3, 4)));
+		}
+		assert_noop!(
+			Quadravote::register_track(Origin::root(), max_tracks as u16 + 1, track_info(3, 4)),
+			Error::<Test>::TooManyTracks
+		);
+		// Registered tracks can still be updated
+		assert_ok!(Quadravote::register_track(Origin::root(), 1, track_info(5, 5)));
+		assert_eq!(Quadravote::track_info(1).unwrap().proposal_period_length, 5);
+	});
+}
+
+#[test]
+fn admin_can_cancel_and_veto_proposals() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let bob = 1u64;
+
+		ProposalDeposit::set(&100);
+		for who in [alice, bob] {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [1u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 3, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [1u8; 32], 1, 0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), [0u8; 32], 2, 0));
+		assert_eq!(Balances::free_balance(&alice), 9_999_740);
+		assert_eq!(Balances::free_balance(&bob), 9_999_946);
+
+		// Only the admin can cancel, the votes on the proposal are refunded straight away
+		// and half of the proposal deposit is slashed
+		assert_noop!(Quadravote::cancel_proposal(Origin::signed(alice), [0u8; 32]), BadOrigin);
+		assert_ok!(Quadravote::cancel_proposal(Origin::root(), [0u8; 32]));
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalCancelled {
+			proposal: [0u8; 32],
+		}));
+		System::assert_has_event(Event::Quadravote(crate::Event::DepositSlashed {
+			proposal: [0u8; 32],
+			who: alice,
+			amount: 50,
+		}));
+		assert_eq!(Balances::free_balance(&alice), 9_999_799);
+		assert_eq!(Balances::reserved_balance(&alice), 151);
+		assert_eq!(Balances::free_balance(&bob), 9_999_950);
+		assert_eq!(crate::VotersOf::<Test>::iter_key_prefix([0u8; 32]).count(), 0);
+		assert_eq!(Quadravote::votes_of(&alice), vec![([1u8; 32], VoteRecord { aye: 1, nay: 0 })]);
+		assert!(!Proposals::<Test>::get(DEFAULT_TRACK)
+			.unwrap()
+			.iter()
+			.any(|p| p.proposal == [0u8; 32]));
+		assert_noop!(
+			Quadravote::cancel_proposal(Origin::root(), [0u8; 32]),
+			Error::<Test>::ProposalDoesNotExist
+		);
+
+		// Proposal period, the remaining proposal won and is vetoed before its enactment
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert!(PendingEnactments::<Test>::contains_key([1u8; 32]));
+		assert_ok!(Quadravote::cancel_proposal(Origin::root(), [1u8; 32]));
+		assert!(!PendingEnactments::<Test>::contains_key([1u8; 32]));
+		run_to_block(System::block_number() + <Test as crate::Config>::EnactmentPeriod::get());
+		assert!(!EnactedProposals::<Test>::contains_key([1u8; 32]));
+	});
+}
+
+#[test]
+fn admin_can_force_phases_and_pause_the_cycle() {
+	new_test_ext().execute_with(|| {
+		let alice = 0u64;
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period, forced to end a block after it started
+		run_to_block(6);
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_noop!(Quadravote::force_end_phase(Origin::signed(alice), DEFAULT_TRACK), BadOrigin);
+		assert_ok!(Quadravote::force_end_phase(Origin::root(), DEFAULT_TRACK));
+		System::assert_has_event(Event::Quadravote(crate::Event::ProposalPeriodEnded {
+			track: DEFAULT_TRACK,
+			block: 6,
+		}));
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+		assert_eq!(Quadravote::blocks_remaining(DEFAULT_TRACK, 6), 5);
+
+		// Paused with 4 blocks of the voting period left, nothing moves
+		run_to_block(7);
+		assert_ok!(Quadravote::pause(Origin::root()));
+		assert_noop!(Quadravote::pause(Origin::root()), Error::<Test>::CyclePaused);
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 1, 0),
+			Error::<Test>::CyclePaused
+		);
+		assert_noop!(
+			Quadravote::force_end_phase(Origin::root(), DEFAULT_TRACK),
+			Error::<Test>::CyclePaused
+		);
+		// A track registered while paused starts its proposal period on registration
+		run_to_block(18);
+		assert_ok!(Quadravote::register_track(Origin::root(), 1, track_info(3, 4)));
+		run_to_block(20);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+
+		// Resumed, the voting period picks up where it was paused and the new track is not
+		// given the pause on top
+		assert_ok!(Quadravote::resume(Origin::root()));
+		System::assert_has_event(Event::Quadravote(crate::Event::CycleResumed { block: 20 }));
+		assert_noop!(Quadravote::resume(Origin::root()), Error::<Test>::NotPaused);
+		assert_eq!(Quadravote::blocks_remaining(DEFAULT_TRACK, 20), 4);
+		assert_eq!(crate::PhaseStart::<Test>::get(1), 18);
+		assert_eq!(Quadravote::blocks_remaining(1, 20), 1);
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 1, 0));
+		run_to_block(23);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Voting);
+		run_to_block(24);
+		assert_eq!(Quadravote::phase(DEFAULT_TRACK), Phase::Proposal);
+	});
+}
+
+#[test]
+fn proposal_deposit_is_slashed_without_quorum() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		let bob = 1u64;
+		ProposalDeposit::set(&100);
+
+		for who in [alice, bob] {
+			assert_ok!(Balances::set_balance(Origin::root(), who, 10_000_000, 0));
+			VotingRegistry::register(Origin::signed(who)).unwrap();
+		}
+		let issuance = Balances::total_issuance();
+
+		// Proposal period, withdrawing a proposal slashes half of its deposit
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		for proposal in [[0u8; 32], [1u8; 32], [2u8; 32]] {
+			assert_ok!(create_proposal(Origin::signed(alice), proposal));
+		}
+		assert_eq!(Balances::reserved_balance(&alice), 300);
+		assert_ok!(Quadravote::withdraw_proposal(Origin::signed(alice), [0u8; 32]));
+		System::assert_has_event(Event::Quadravote(crate::Event::DepositSlashed {
+			proposal: [0u8; 32],
+			who: alice,
+			amount: 50,
+		}));
+		assert_eq!(Balances::reserved_balance(&alice), 200);
+		assert_eq!(Balances::free_balance(&alice), 9_999_750);
+
+		// Voting period, only one of the proposals gets any votes
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(bob), [1u8; 32], 1, 0));
+
+		// Proposal period, the deposit of the proposal without a quorum is slashed
+		current_height += period_length;
+		run_to_block(current_height.into());
+		System::assert_has_event(Event::Quadravote(crate::Event::DepositSlashed {
+			proposal: [2u8; 32],
+			who: alice,
+			amount: 50,
+		}));
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert_eq!(Balances::free_balance(&alice), 9_999_900);
+		assert_eq!(Balances::total_issuance(), issuance - 100);
+	});
+}
+
+#[test]
+fn voice_credits_pay_for_votes_without_reserving() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		UseVoiceCredits::set(&true);
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Let the default track spend all 100 credits of the registry
+		let mut info = Quadravote::default_track_info();
+		info.max_votes_per_account = 100;
+		assert_ok!(Quadravote::register_track(Origin::root(), DEFAULT_TRACK, info));
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+		assert_ok!(create_proposal(Origin::signed(alice), [1u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// 8 votes spend 64 of the 100 voice credits, nothing is reserved beyond the
+		// 50 reserved by the registry
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 8, 0));
+		assert_eq!(Balances::free_balance(&alice), 9_999_950);
+		assert_eq!(Quadravote::reserve_of(&alice), 0);
+
+		// 7 more votes on another proposal would spend 113 credits, 6 spend exactly 100
+		// even though that is more than `MaxVotesPerAccount` votes
+		assert_eq!(Quadravote::quote_vote(&alice, &[1u8; 32], 7, 0), None);
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [1u8; 32], 7, 0),
+			Error::<Test>::AllVotesCastForAccount
+		);
+		assert_eq!(Quadravote::quote_vote(&alice, &[1u8; 32], 6, 0), Some(0));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [1u8; 32], 6, 0));
+
+		// Lowering votes frees their credits
+		assert_ok!(Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 5, 0));
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 4, 0),
+			Error::<Test>::AllVotesCastForAccount
+		);
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 3, 0));
+		assert_eq!(
+			Quadravote::votes_of(&alice),
+			vec![
+				([0u8; 32], VoteRecord { aye: 8, nay: 0 }),
+				([1u8; 32], VoteRecord { aye: 6, nay: 0 })
+			]
+		);
+
+		// The credits are issued again for the next round
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_eq!(Balances::free_balance(&alice), 9_999_950);
+		assert_ok!(create_proposal(Origin::signed(alice), [2u8; 32]));
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [2u8; 32], 10, 0));
+	});
+}
+
+#[test]
+fn voice_credits_are_capped_by_the_track_budget() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		UseVoiceCredits::set(&true);
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// The default track allows 10 of the 100 credits to be spent, 4 votes would spend 16
+		assert_eq!(Quadravote::quote_vote(&alice, &[0u8; 32], 4, 0), None);
+		assert_noop!(
+			Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 4, 0),
+			Error::<Test>::AllVotesCastForAccount
+		);
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 3, 0));
+
+		// The electorate counts the votes a voter can afford, not the credits
+		assert_eq!(CostModel::max_votes::<Quadratic>(10), 3);
+		assert_eq!(CostModel::max_votes::<Quadratic>(100), 10);
+		assert_eq!(CostModel::max_votes::<Linear>(10), 10);
+		UseVoiceCredits::set(&false);
+		assert_eq!(CostModel::max_votes::<Quadratic>(10), 10);
+	});
+}
+
+#[test]
+fn voice_credit_voters_can_not_deregister_before_the_tally() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		UseVoiceCredits::set(&true);
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+
+		// Voting period, the credits alice spent can not be moved to another registration
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 3, 0));
+		assert_noop!(
+			VotingRegistry::deregister(Origin::signed(alice)),
+			pallet_votingregistry::Error::<Test>::IdentityInUse
+		);
+
+		// Retracting the votes frees the registration
+		assert_ok!(Quadravote::adjust_vote(Origin::signed(alice), [0u8; 32], 0, 0));
+		assert_ok!(VotingRegistry::deregister(Origin::signed(alice)));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 3, 0));
+
+		// Once the round is tallied the registration can go
+		current_height += period_length;
+		run_to_block(current_height.into());
+		assert_ok!(VotingRegistry::deregister(Origin::signed(alice)));
+	});
+}
+
+#[test]
+fn votes_are_priced_by_the_vote_cost_function() {
+	new_test_ext().execute_with(|| {
+		let period_length = PERIOD_LENGTH;
+		let alice = 0u64;
+		assert_eq!(Linear::cost(5), Some(5));
+		assert_eq!(Quadratic::cost(5), Some(25));
+		assert_eq!(Power::<3>::cost(5), Some(125));
+		assert_eq!(Power::<5>::cost(u32::MAX), None);
+		// No votes cost nothing, whatever the exponent
+		assert_eq!(Power::<3>::cost(0), Some(0));
+
+		LinearVoteCost::set(&true);
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		VotingRegistry::register(Origin::signed(alice)).unwrap();
+
+		// Proposal period
+		let mut current_height = period_length;
+		run_to_block(current_height.into());
+		assert_ok!(create_proposal(Origin::signed(alice), [0u8; 32]));
+
+		// Voting period
+		current_height += period_length;
+		run_to_block(current_height.into());
+
+		// Every vote reserves the same, on top of the 50 reserved by the registry
+		assert_eq!(Quadravote::quote_vote(&alice, &[0u8; 32], 5, 0), Some(5));
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0));
+		assert_eq!(Balances::free_balance(&alice), 9_999_945);
+		assert_ok!(Quadravote::cast_vote(Origin::signed(alice), [0u8; 32], 5, 0));
+		assert_eq!(Balances::free_balance(&alice), 9_999_940);
+		assert_eq!(Quadravote::reserve_of(&alice), 10);
+	});
+}
@@ -0,0 +1,125 @@
+//! Rules deciding whether a proposal passes at the end of a voting period.
+//!
+//! A proposal first has to reach the quorum set by `MinimumTurnout` and `MinimumVoters`,
+//! after which the `ApprovalThreshold` configured for the pallet decides on the votes.
+//! The `SelectionMode` then picks the winners from the approved proposals. Tracks that need
+//! a threshold other than the configured one pick a `ThresholdRule`.
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::Get;
+use scale_info::TypeInfo;
+use sp_runtime::Perbill;
+
+/// The tally of a proposal at the end of a voting period.
+#[derive(Encode, Decode, Debug, TypeInfo, Eq, PartialEq, Clone, Copy)]
+pub struct Tally {
+	pub votes_for: u32,
+	pub votes_against: u32,
+	/// How many accounts hold votes on the proposal.
+	pub voters: u32,
+}
+
+impl Tally {
+	/// All votes cast on the proposal, for and against.
+	pub fn turnout(&self) -> u32 {
+		self.votes_for.saturating_add(self.votes_against)
+	}
+
+	/// The net quadratic score of the proposal, votes for minus votes against.
+	pub fn score(&self) -> i64 {
+		self.votes_for as i64 - self.votes_against as i64
+	}
+}
+
+/// Why a proposal passed or failed, reported once its voting period is over.
+#[derive(Encode, Decode, Debug, TypeInfo, Eq, PartialEq, Clone, Copy)]
+pub enum Outcome {
+	/// The proposal reached the quorum and the approval threshold.
+	Approved,
+	/// Too few votes were cast on the proposal.
+	TurnoutTooLow,
+	/// Too few accounts voted on the proposal.
+	TooFewVoters,
+	/// The proposal reached the quorum but not the approval threshold.
+	ThresholdNotMet,
+	/// The proposal was approved but ranked below the proposals picked by `TopK`.
+	OutRanked,
+}
+
+impl Outcome {
+	/// Whether the proposal reached the quorum, regardless of whether it won.
+	pub fn reached_quorum(&self) -> bool {
+		!matches!(self, Outcome::TurnoutTooLow | Outcome::TooFewVoters)
+	}
+}
+
+/// How the winners of a voting period are picked from the approved proposals.
+#[derive(Encode, Decode, Debug, TypeInfo, MaxEncodedLen, Eq, PartialEq, Clone, Copy)]
+pub enum SelectionMode {
+	/// Every approved proposal wins.
+	Majority,
+	/// Only the given number of approved proposals with the highest score win. Ties go to
+	/// the proposal with more votes for, then to the one created first.
+	TopK(u32),
+}
+
+/// Decides whether a proposal that reached the quorum is approved.
+pub trait ApprovalThreshold {
+	/// Whether `tally` is approved. `electorate` is the most votes that could have been
+	/// cast on the proposal during the voting period.
+	fn approved(tally: &Tally, electorate: u32) -> bool;
+}
+
+/// More votes for than against.
+pub struct SimpleMajority;
+
+impl ApprovalThreshold for SimpleMajority {
+	fn approved(tally: &Tally, _electorate: u32) -> bool {
+		tally.votes_for > tally.votes_against
+	}
+}
+
+/// More than `Share` of the turnout voted for, e.g. two thirds.
+pub struct SuperMajority<Share>(sp_std::marker::PhantomData<Share>);
+
+impl<Share: Get<Perbill>> ApprovalThreshold for SuperMajority<Share> {
+	fn approved(tally: &Tally, electorate: u32) -> bool {
+		ThresholdRule::SuperMajority(Share::get()).approved(tally, electorate)
+	}
+}
+
+/// Adaptive quorum biasing with a positive turnout bias: a low turnout needs a
+/// super-majority to pass, which lowers to a simple majority as turnout approaches the
+/// electorate. Approved when `against / sqrt(turnout) < for / sqrt(electorate)`.
+pub struct PositiveTurnoutBias;
+
+impl ApprovalThreshold for PositiveTurnoutBias {
+	fn approved(tally: &Tally, electorate: u32) -> bool {
+		// Both sides are squared to stay in integers.
+		let votes_for = tally.votes_for as u128;
+		let votes_against = tally.votes_against as u128;
+		let (turnout, electorate) = (tally.turnout() as u128, electorate as u128);
+		votes_against * votes_against * electorate < votes_for * votes_for * turnout
+	}
+}
+
+/// One of the approval thresholds above, picked at runtime, e.g. by a track.
+#[derive(Encode, Decode, Debug, TypeInfo, MaxEncodedLen, Eq, PartialEq, Clone, Copy)]
+pub enum ThresholdRule {
+	SimpleMajority,
+	/// More than the given share of the turnout voted for.
+	SuperMajority(Perbill),
+	PositiveTurnoutBias,
+}
+
+impl ThresholdRule {
+	/// Whether `tally` is approved, see `ApprovalThreshold::approved`.
+	pub fn approved(&self, tally: &Tally, electorate: u32) -> bool {
+		match self {
+			ThresholdRule::SimpleMajority => SimpleMajority::approved(tally, electorate),
+			ThresholdRule::SuperMajority(share) =>
+				tally.votes_for > share.mul_floor(tally.turnout()),
+			ThresholdRule::PositiveTurnoutBias => PositiveTurnoutBias::approved(tally, electorate),
+		}
+	}
+}
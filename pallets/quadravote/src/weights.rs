@@ -0,0 +1,277 @@
+//! Weights for pallet_quadravote
+//!
+//! These are placeholder weights that have not been measured. Regenerate this file with
+//! `scripts/benchmark-weights.sh` on reference hardware before the pallet is deployed.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_quadravote.
+pub trait WeightInfo {
+	fn create_proposal(p: u32, ) -> Weight;
+	fn withdraw_proposal(p: u32, ) -> Weight;
+	fn cast_vote(p: u32, ) -> Weight;
+	fn adjust_vote(p: u32, ) -> Weight;
+	fn on_initialize_idle() -> Weight;
+	fn on_initialize_end_proposal_period() -> Weight;
+	fn on_initialize_end_voting_period(p: u32, v: u32, ) -> Weight;
+	fn on_initialize_refund_voters(v: u32, ) -> Weight;
+	fn claim_refund() -> Weight;
+	fn enact() -> Weight;
+	fn propose_repeal(p: u32, ) -> Weight;
+	fn create_funding_proposal(p: u32, ) -> Weight;
+	fn contribute(c: u32, ) -> Weight;
+	fn on_initialize_distribute_matching(p: u32, ) -> Weight;
+	fn cancel_proposal(v: u32, ) -> Weight;
+	fn pause() -> Weight;
+	fn resume(t: u32, ) -> Weight;
+	fn register_track() -> Weight;
+	fn remove_track() -> Weight;
+}
+
+/// Placeholder weights for pallet_quadravote, see the module documentation.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn create_proposal(p: u32, ) -> Weight {
+		(41_306_000 as Weight)
+			.saturating_add((152_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn withdraw_proposal(p: u32, ) -> Weight {
+		(36_954_000 as Weight)
+			.saturating_add((171_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn cast_vote(p: u32, ) -> Weight {
+		(38_402_000 as Weight)
+			.saturating_add((264_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn adjust_vote(p: u32, ) -> Weight {
+		(35_917_000 as Weight)
+			.saturating_add((251_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn on_initialize_idle() -> Weight {
+		(3_412_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+	}
+	fn on_initialize_end_proposal_period() -> Weight {
+		(6_205_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn on_initialize_end_voting_period(p: u32, v: u32, ) -> Weight {
+		(14_930_000 as Weight)
+			.saturating_add((31_540_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((22_874_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes((9 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn on_initialize_refund_voters(v: u32, ) -> Weight {
+		(4_127_000 as Weight)
+			.saturating_add((21_653_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn claim_refund() -> Weight {
+		(29_846_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn enact() -> Weight {
+		(12_503_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn propose_repeal(p: u32, ) -> Weight {
+		(43_718_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn create_funding_proposal(p: u32, ) -> Weight {
+		(42_574_000 as Weight)
+			.saturating_add((149_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn contribute(c: u32, ) -> Weight {
+		(45_163_000 as Weight)
+			.saturating_add((118_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn on_initialize_distribute_matching(p: u32, ) -> Weight {
+		(5_338_000 as Weight)
+			.saturating_add((42_907_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+	}
+	fn cancel_proposal(v: u32, ) -> Weight {
+		(33_406_000 as Weight)
+			.saturating_add((18_233_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn pause() -> Weight {
+		(9_014_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn resume(t: u32, ) -> Weight {
+		(10_738_000 as Weight)
+			.saturating_add((2_614_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(t as Weight)))
+	}
+	fn register_track() -> Weight {
+		(14_207_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn remove_track() -> Weight {
+		(19_842_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn create_proposal(p: u32, ) -> Weight {
+		(41_306_000 as Weight)
+			.saturating_add((152_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn withdraw_proposal(p: u32, ) -> Weight {
+		(36_954_000 as Weight)
+			.saturating_add((171_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn cast_vote(p: u32, ) -> Weight {
+		(38_402_000 as Weight)
+			.saturating_add((264_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn adjust_vote(p: u32, ) -> Weight {
+		(35_917_000 as Weight)
+			.saturating_add((251_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn on_initialize_idle() -> Weight {
+		(3_412_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+	}
+	fn on_initialize_end_proposal_period() -> Weight {
+		(6_205_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn on_initialize_end_voting_period(p: u32, v: u32, ) -> Weight {
+		(14_930_000 as Weight)
+			.saturating_add((31_540_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((22_874_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((9 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn on_initialize_refund_voters(v: u32, ) -> Weight {
+		(4_127_000 as Weight)
+			.saturating_add((21_653_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn claim_refund() -> Weight {
+		(29_846_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn enact() -> Weight {
+		(12_503_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn propose_repeal(p: u32, ) -> Weight {
+		(43_718_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn create_funding_proposal(p: u32, ) -> Weight {
+		(42_574_000 as Weight)
+			.saturating_add((149_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn contribute(c: u32, ) -> Weight {
+		(45_163_000 as Weight)
+			.saturating_add((118_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn on_initialize_distribute_matching(p: u32, ) -> Weight {
+		(5_338_000 as Weight)
+			.saturating_add((42_907_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(p as Weight)))
+	}
+	fn cancel_proposal(v: u32, ) -> Weight {
+		(33_406_000 as Weight)
+			.saturating_add((18_233_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(v as Weight)))
+	}
+	fn pause() -> Weight {
+		(9_014_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn resume(t: u32, ) -> Weight {
+		(10_738_000 as Weight)
+			.saturating_add((2_614_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(t as Weight)))
+	}
+	fn register_track() -> Weight {
+		(14_207_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn remove_track() -> Weight {
+		(19_842_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+}
@@ -1,20 +1,37 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-votingregistry
 
 use super::*;
 
-#[allow(unused)]
-use crate::Pallet as Template;
+use crate::Pallet as VotingRegistryPallet;
 use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::{Currency, Get};
 use frame_system::RawOrigin;
 
+/// A whitelisted account with enough free balance to cover the registration reserve.
+fn funded_caller<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(
+		&caller,
+		T::Currency::minimum_balance() + T::ReserveAmount::get(),
+	);
+	caller
+}
+
 benchmarks! {
-	do_something {
-		let s in 0 .. 100;
-		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+	register {
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(VotingRegistry::<T>::contains_key(&caller));
+	}
+
+	deregister {
+		let caller = funded_caller::<T>();
+		VotingRegistryPallet::<T>::register(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller.clone()))
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert!(!VotingRegistry::<T>::contains_key(&caller));
 	}
 }
 
-impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test,);
+impl_benchmark_test_suite!(VotingRegistryPallet, crate::mock::new_test_ext(), crate::mock::Test,);
@@ -1,6 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use corpus_traits::IdentityInterface;
+use corpus_traits::{IdentityInUse, IdentityInterface};
 pub use pallet::*;
 
 /// Custom type to simplify Config specification.
@@ -13,6 +13,9 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod weights;
+pub use weights::*;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -22,7 +25,7 @@ pub mod pallet {
 	};
 	use frame_system::pallet_prelude::*;
 
-	type BalanceOf<T> =
+	pub(super) type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -36,6 +39,17 @@ pub mod pallet {
 		/// The amount each registered voter has to have bonded to vote
 		#[pallet::constant]
 		type ReserveAmount: Get<BalanceOf<Self>>;
+
+		/// The voice credits every registered voter receives for each voting round
+		#[pallet::constant]
+		type VoiceCredits: Get<u32>;
+
+		/// Whether a registered voter still has votes resting on its registration, it can not
+		/// deregister until they are tallied
+		type IdentityInUse: IdentityInUse<Self::AccountId>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
@@ -65,6 +79,8 @@ pub mod pallet {
 		AlreadyRegistered,
 		/// If an account tries to deregister while not existing in the registry.
 		NotRegistered,
+		/// If an account tries to deregister while its registration still backs votes.
+		IdentityInUse,
 	}
 
 	#[pallet::hooks]
@@ -76,7 +92,7 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Register to vote.
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::register())]
 		pub fn register(origin: OriginFor<T>) -> DispatchResult {
 			// Transaction is signed
 			let who = ensure_signed(origin)?;
@@ -97,10 +113,12 @@ pub mod pallet {
 		}
 
 		/// Unbond the tokens behind your registration.
-		#[pallet::weight(1_000)]
+		#[pallet::weight(T::WeightInfo::deregister())]
 		pub fn deregister(origin: OriginFor<T>) -> DispatchResult {
 			// Transaction is signed
 			let who = ensure_signed(origin)?;
+			// Are the votes paid for by the registration tallied
+			ensure!(!T::IdentityInUse::in_use(&who), Error::<T>::IdentityInUse);
 			// Is the sender registered
 			let maybe_sender = VotingRegistry::<T>::take(&who);
 			match maybe_sender {
@@ -123,4 +141,17 @@ impl<T: Config> IdentityInterface<T::AccountId> for Pallet<T> {
 	fn is_identified(who: &T::AccountId) -> bool {
 		VotingRegistry::<T>::contains_key(who)
 	}
+
+	fn voice_credits(who: &T::AccountId) -> u32 {
+		match VotingRegistry::<T>::contains_key(who) {
+			true => T::VoiceCredits::get(),
+			false => 0,
+		}
+	}
+
+	/// Inserts `who` into the registry without reserving anything.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn ensure_identified(who: &T::AccountId) {
+		VotingRegistry::<T>::insert(who, BalanceOf::<T>::default());
+	}
 }
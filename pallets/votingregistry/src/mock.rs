@@ -1,7 +1,7 @@
 use crate as pallet_votingregistry;
 use frame_support::{
 	parameter_types,
-	traits::{ConstU64, Everything},
+	traits::{ConstU32, ConstU64, Everything},
 };
 
 use frame_system as system;
@@ -33,6 +33,7 @@ frame_support::construct_runtime!(
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub const SS58Prefix: u8 = 42;
+	pub static InUse: bool = false;
 }
 
 impl system::Config for Test {
@@ -62,10 +63,22 @@ impl system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+// Registrations back votes while `InUse` is set.
+pub struct IdentityInUse;
+
+impl corpus_traits::IdentityInUse<AccountId> for IdentityInUse {
+	fn in_use(_who: &AccountId) -> bool {
+		InUse::get()
+	}
+}
+
 impl pallet_votingregistry::Config for Test {
 	type Event = Event;
 	type Currency = Balances;
 	type ReserveAmount = ConstU64<50>;
+	type VoiceCredits = ConstU32<100>;
+	type IdentityInUse = IdentityInUse;
+	type WeightInfo = ();
 }
 
 impl pallet_balances::Config for Test {
@@ -1,4 +1,5 @@
 use crate::{mock::*, Error};
+use corpus_traits::IdentityInterface;
 use frame_support::{assert_noop, assert_ok, error::BadOrigin};
 
 #[test]
@@ -43,6 +44,13 @@ fn call_deregister() {
 		VotingRegistry::register(Origin::signed(alice)).unwrap();
 		// Does not work unsigned
 		assert_noop!(VotingRegistry::deregister(Origin::none()), BadOrigin);
+		// Does not work while the registration backs votes
+		InUse::set(&true);
+		assert_noop!(
+			VotingRegistry::deregister(Origin::signed(alice)),
+			Error::<Test>::IdentityInUse
+		);
+		InUse::set(&false);
 		// Does work signed and registered
 		assert_ok!(VotingRegistry::deregister(Origin::signed(alice)));
 		// Does not work if already deregistered
@@ -52,3 +60,16 @@ fn call_deregister() {
 		);
 	})
 }
+
+#[test]
+fn registered_voters_receive_voice_credits() {
+	new_test_ext().execute_with(|| {
+		let alice = 1u64;
+		assert_ok!(Balances::set_balance(Origin::root(), alice, 10_000_000, 0));
+		assert_eq!(VotingRegistry::voice_credits(&alice), 0);
+		assert_ok!(VotingRegistry::register(Origin::signed(alice)));
+		assert_eq!(VotingRegistry::voice_credits(&alice), 100);
+		assert_ok!(VotingRegistry::deregister(Origin::signed(alice)));
+		assert_eq!(VotingRegistry::voice_credits(&alice), 0);
+	})
+}
@@ -27,7 +27,7 @@ use sp_version::RuntimeVersion;
 
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{ConstU128, Everything},
+	traits::{ConstU32, Contains, EqualPrivilegeOnly, Everything},
 	weights::{
 		constants::WEIGHT_PER_SECOND, ConstantMultiplier, DispatchClass, Weight,
 		WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
@@ -116,6 +116,11 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	(
+		pallet_quadravote::migrations::v1::MigrateToV1<Runtime>,
+		pallet_quadravote::migrations::v2::MigrateToV2<Runtime, OldPeriodLength>,
+		pallet_quadravote::migrations::v3::MigrateToV3<Runtime>,
+	),
 >;
 
 /// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
@@ -173,10 +178,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("template-parachain"),
 	impl_name: create_runtime_str!("template-parachain"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 4,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -456,28 +461,139 @@ impl pallet_collator_selection::Config for Runtime {
 
 // pallet_quadravote parameters.
 parameter_types! {
-	pub const PeriodLength: u32 = 4;
+	// A short window to submit proposals, a longer one to vote on them.
+	pub const ProposalPeriodLength: u32 = 4;
+	pub const VotingPeriodLength: u32 = 8;
+	pub const CooldownPeriodLength: u32 = 2;
+	// The length of both periods before they were decoupled, see `MigrateToV2`.
+	pub const OldPeriodLength: u32 = 4;
 	pub const MaxProposals: u32 = 1;
 	pub const MaxVotesPerAccount: u32 = 10;
-	pub const MaxVotersPerSession: u32 = 10;
+	pub const VoteCostUnit: Balance = 100 * MILLIUNIT;
+	// Room for a real electorate, ten slots let ten identities shut everyone else out of a
+	// round. Voters are refunded lazily, so this does not need to fit in a block.
+	pub const MaxVotersPerSession: u32 = 500;
+	pub const MaxRefundsPerBlock: u32 = 10;
+	pub const MaxTitleLength: u32 = 128;
+	pub const MaxDescriptionUriLength: u32 = 256;
+	pub const MaxCallLength: u32 = 16 * 1024;
+	// Winning proposals are dispatched as root, `EnactableCalls` limits what they can carry.
+	pub EnactmentOrigin: frame_system::RawOrigin<AccountId> = frame_system::RawOrigin::Root;
+	// The winners of a round are enacted in a single block, leave room for the rest of it.
+	pub const MaxCallWeight: Weight = MAXIMUM_BLOCK_WEIGHT / 4;
+	// Two days between a proposal winning and its call being dispatched, for those who
+	// disagree with the decision to react, e.g. by getting the admin to veto it.
+	pub const EnactmentPeriod: BlockNumber = 2 * DAYS;
+	// A proposal needs a broad quorum before it can win, not a couple of identities.
+	pub const MinimumTurnout: u32 = 100;
+	pub const MinimumVoters: u32 = 25;
+	pub const Selection: pallet_quadravote::SelectionMode =
+		pallet_quadravote::SelectionMode::Majority;
+	pub const MetadataDepositBase: Balance = 10 * MILLIUNIT;
+	pub const MetadataDepositPerByte: Balance = 10 * MICROUNIT;
+	pub const ProposalDeposit: Balance = 100 * MILLIUNIT;
+	pub const DepositSlash: Perbill = Perbill::from_percent(50);
+	pub const QuadravotePalletId: PalletId = PalletId(*b"py/qvote");
+	pub const MaxContributors: u32 = 100;
+	pub const MaxTracks: u32 = 8;
+	pub const MaxTrackNameLength: u32 = 32;
+}
+
+/// The calls a quadravote proposal may carry. They are dispatched as root, so only calls
+/// tuning the voting tracks and the collator set are let through. Runtime upgrades, storage
+/// writes and forced balance changes stay out of reach of the vote.
+pub struct EnactableCalls;
+
+impl Contains<Call> for EnactableCalls {
+	fn contains(call: &Call) -> bool {
+		matches!(
+			call,
+			Call::System(frame_system::Call::remark { .. }) |
+				Call::Quadravote(
+					pallet_quadravote::Call::register_track { .. } |
+						pallet_quadravote::Call::remove_track { .. }
+				) | Call::CollatorSelection(
+				pallet_collator_selection::Call::set_desired_candidates { .. } |
+					pallet_collator_selection::Call::set_candidacy_bond { .. }
+			)
+		)
+	}
 }
 
 impl pallet_quadravote::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
 	type IdentityProvider = VotingRegistry;
-	type PeriodLength = PeriodLength;
+	type ProposalPeriodLength = ProposalPeriodLength;
+	type VotingPeriodLength = VotingPeriodLength;
+	type CooldownPeriodLength = CooldownPeriodLength;
 	type MaxProposals = MaxProposals;
 	type MaxVotesPerAccount = MaxVotesPerAccount;
+	type VoteCostUnit = VoteCostUnit;
+	type VoteCostFunction = pallet_quadravote::Quadratic;
+	type CostModel = pallet_quadravote::ReserveFunds;
 	type MaxVotersPerSession = MaxVotersPerSession;
+	type MaxRefundsPerBlock = MaxRefundsPerBlock;
+	type MaxTitleLength = MaxTitleLength;
+	type MaxDescriptionUriLength = MaxDescriptionUriLength;
+	type MaxCallLength = MaxCallLength;
+	type Proposal = Call;
+	type EnactmentOrigin = EnactmentOrigin;
+	type ProposalFilter = EnactableCalls;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxCallWeight = MaxCallWeight;
+	type EnactmentPeriod = EnactmentPeriod;
+	type MinimumTurnout = MinimumTurnout;
+	type MinimumVoters = MinimumVoters;
+	type Threshold = pallet_quadravote::SimpleMajority;
+	type Selection = Selection;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ProposalDeposit = ProposalDeposit;
+	type DepositSlash = DepositSlash;
+	type Slash = ();
+	type PalletId = QuadravotePalletId;
+	type MaxContributors = MaxContributors;
+	type MaxTracks = MaxTracks;
+	type MaxTrackNameLength = MaxTrackNameLength;
+	type WeightInfo = pallet_quadravote::weights::SubstrateWeight<Runtime>;
 }
 
-parameter_types! {}
+parameter_types! {
+	// Every registration is handed a voice credit budget for each round, registering many
+	// identities has to be expensive.
+	pub const VotingRegistryDeposit: Balance = 10 * UNIT;
+}
 
 impl pallet_votingregistry::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
-	type ReserveAmount = ConstU128<50>;
+	type ReserveAmount = VotingRegistryDeposit;
+	type VoiceCredits = ConstU32<100>;
+	type IdentityInUse = Quadravote;
+	type WeightInfo = pallet_votingregistry::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) *
+		RuntimeBlockWeights::get().max_block;
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type PreimageProvider = ();
+	type NoPreimagePostponement = ();
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -522,6 +638,8 @@ construct_runtime!(
 		VotingRegistry: pallet_votingregistry,
 		// Quadratic Voting.
 		Quadravote: pallet_quadravote = 51,
+		// Enactment of winning proposals.
+		Scheduler: pallet_scheduler = 52,
 		// Sudo
 		Sudo: pallet_sudo = 99,
 	}
@@ -540,6 +658,8 @@ mod benches {
 		[pallet_timestamp, Timestamp]
 		[pallet_collator_selection, CollatorSelection]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
+		[pallet_quadravote, Quadravote]
+		[pallet_votingregistry, VotingRegistry]
 	);
 }
 
@@ -644,6 +764,42 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_quadravote_runtime_api::QuadravoteApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn tracks() -> Vec<(pallet_quadravote::TrackId, Vec<u8>)> {
+			Quadravote::tracks()
+		}
+
+		fn phase(track: pallet_quadravote::TrackId) -> Option<pallet_quadravote_runtime_api::Phase> {
+			Quadravote::track_info(track).map(|_| Quadravote::phase(track))
+		}
+
+		fn blocks_remaining(track: pallet_quadravote::TrackId) -> Option<BlockNumber> {
+			Quadravote::track_info(track)
+				.map(|_| Quadravote::blocks_remaining(track, System::block_number()))
+		}
+
+		fn tallies(track: pallet_quadravote::TrackId) -> Vec<pallet_quadravote_runtime_api::VotingProposal> {
+			Quadravote::tallies(track)
+		}
+
+		fn votes_of(who: AccountId) -> Vec<([u8; 32], pallet_quadravote_runtime_api::VoteRecord)> {
+			Quadravote::votes_of(&who)
+		}
+
+		fn reserve_of(who: AccountId) -> Balance {
+			Quadravote::reserve_of(&who)
+		}
+
+		fn quote_vote(
+			who: AccountId,
+			proposal: [u8; 32],
+			votes_for: u32,
+			votes_against: u32,
+		) -> Option<Balance> {
+			Quadravote::quote_vote(&who, &proposal, votes_for, votes_against)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -0,0 +1,22 @@
+#!/usr/bin/env sh
+
+# Regenerate the weights of the corpus pallets. Run on reference hardware after building
+# the node with `cargo build --release --features runtime-benchmarks`.
+
+set -e
+
+benchmark() {
+	./target/release/parachain-template-node benchmark pallet \
+		--chain dev \
+		--execution wasm \
+		--wasm-execution compiled \
+		--pallet "$1" \
+		--extrinsic '*' \
+		--steps 50 \
+		--repeat 20 \
+		--template ./.maintain/frame-weight-template.hbs \
+		--output "$2"
+}
+
+benchmark pallet_quadravote ./pallets/quadravote/src/weights.rs
+benchmark pallet_votingregistry ./pallets/votingregistry/src/weights.rs